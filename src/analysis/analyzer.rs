@@ -17,12 +17,13 @@ use {
         Deserialize,
         Serialize,
     },
+    termimad::crossbeam::channel::Receiver,
 };
 
 /// A stateless operator building a report from a list of command output lines.
 ///
 /// Implementation routing will probably change at some point
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AnalyzerRef {
     #[default]
@@ -40,10 +41,22 @@ pub enum AnalyzerRef {
     SwiftBuild,
     SwiftLint,
     Typescript,
+    /// An analyzer defined in the `analyzers` section of the configuration
+    #[serde(untagged)]
+    Custom(String),
+    /// A helper program receiving the lines and answering their analysis,
+    /// eg `analyzer = { command = ["my-analyzer", "--json"] }`
+    #[serde(untagged)]
+    External {
+        command: Vec<String>,
+    },
 }
 
 impl AnalyzerRef {
-    pub fn create_analyzer(self) -> Box<dyn Analyzer> {
+    pub fn create_analyzer(
+        &self,
+        settings: &Settings,
+    ) -> Box<dyn Analyzer> {
         match self {
             Self::Standard => Box::new(standard::StandardAnalyzer::default()),
             Self::Biome => Box::new(biome::BiomeAnalyzer::default()),
@@ -59,10 +72,35 @@ impl AnalyzerRef {
             Self::SwiftBuild => Box::new(swift::build::SwiftBuildAnalyzer::default()),
             Self::SwiftLint => Box::new(swift::lint::SwiftLintAnalyzer::default()),
             Self::Typescript => Box::new(typescript::TypescriptAnalyzer::default()),
+            Self::External { command } => Box::new(ExternalAnalyzer::new(command.clone())),
+            Self::Custom(name) => match settings.analyzers.get(name) {
+                Some(config) => Box::new(RegexAnalyzer::new(config.clone())),
+                None => {
+                    // settings checking should prevent this
+                    warn!("unknown analyzer: {name:?}");
+                    Box::new(standard::StandardAnalyzer::default())
+                }
+            },
         }
     }
 }
 
+impl AnalyzerRef {
+    /// Whether the tool of this analyzer gives locations without the
+    /// source lines, which bacon may then display
+    pub fn lacks_source_snippets(&self) -> bool {
+        matches!(
+            self,
+            Self::Biome | Self::Eslint | Self::Go | Self::PythonRuff | Self::SwiftLint
+        )
+    }
+    /// Whether the columns of the locations given by the tool are
+    /// counted in bytes rather than in chars
+    pub fn has_byte_columns(&self) -> bool {
+        matches!(self, Self::Go)
+    }
+}
+
 pub trait Analyzer {
     fn start(
         &mut self,
@@ -75,5 +113,12 @@ pub trait Analyzer {
         command_output: &mut CommandOutput,
     );
 
+    /// Called when the command's output ended. An analyzer which needs
+    /// time to finish its analysis returns a receiver getting a message
+    /// when `build_report` can be called without blocking
+    fn end_of_output(&mut self) -> Option<Receiver<()>> {
+        None
+    }
+
     fn build_report(&mut self) -> anyhow::Result<Report>;
 }
@@ -11,8 +11,10 @@ use {
     cargo_metadata::{
         Message,
         diagnostic::{
+            Applicability,
             Diagnostic,
             DiagnosticLevel,
+            DiagnosticSpanLine,
         },
     },
     lazy_regex::*,
@@ -21,17 +23,25 @@ use {
 /// An analyzer able to read the output
 /// of `cargo check --message-format=json-diagnostic-rendered-ansi`
 ///
-/// In order to be useful, this analyzer would have to read more than
-/// just the 'rendered' field of the diagnostic. It would have to read
-/// for example the spans, detect what's a suggestion, etc.
+/// The 'rendered' field of the diagnostics is displayed, while their
+/// spans, codes, children, and suggested replacements are kept as
+/// structured diagnostics of the report items, so that machine
+/// applicable suggestions can be applied.
 /// See <https://github.com/Canop/bacon/issues/249>
-///
-/// There are so many problems with this approach, though, that I'm
-/// not sure this is worth it.
 #[derive(Default)]
 pub struct CargoJsonAnalyzer {
     analysis: Vec<(LineAnalysis, TLine)>,
     exports: Vec<CargoJsonExport>,
+    /// structured diagnostics of the errors and warnings, in order
+    diagnostics: Vec<(DiagnosticKey, ItemDiagnostic)>,
+}
+
+/// What identifies the report item of a diagnostic: its rendered title
+/// and location, as they appear in the report
+#[derive(Debug, PartialEq, Eq)]
+struct DiagnosticKey {
+    title: String,
+    location: Option<String>,
 }
 
 impl Analyzer for CargoJsonAnalyzer {
@@ -41,6 +51,7 @@ impl Analyzer for CargoJsonAnalyzer {
     ) {
         self.analysis.clear();
         self.exports.clear();
+        self.diagnostics.clear();
         for (name, export_settings) in &mission.settings.exports.exports {
             if export_settings.exporter == Exporter::Analyser {
                 let export = CargoJsonExport::new(name.clone(), export_settings);
@@ -74,6 +85,24 @@ impl Analyzer for CargoJsonAnalyzer {
 
     fn build_report(&mut self) -> Result<Report> {
         let mut report = build_report_from_analysis(self.analysis.drain(..));
+        // items are sorted, and some aren't diagnostics, so each item
+        // gets the first remaining diagnostic with the same title and location
+        let mut item_diagnostics = Vec::new();
+        for line in &report.lines {
+            if !matches!(line.line_type, LineType::Title(Kind::Error | Kind::Warning)) {
+                continue;
+            }
+            let key = DiagnosticKey {
+                title: line.content.to_raw(),
+                location: report.item_location(line.item_idx).map(String::from),
+            };
+            if let Some(idx) = self.diagnostics.iter().position(|(k, _)| *k == key) {
+                let (_, diagnostic) = self.diagnostics.remove(idx);
+                item_diagnostics.push((line.item_idx, diagnostic));
+            }
+        }
+        self.diagnostics.clear();
+        report.item_diagnostics.extend(item_diagnostics);
         for export in self.exports.drain(..) {
             report.analyzer_exports.insert(export.name, export.export);
         }
@@ -106,6 +135,12 @@ impl CargoJsonAnalyzer {
         for export in &mut self.exports {
             export.receive_diagnostic(&diagnostic);
         }
+        let structured = match diagnostic.level {
+            DiagnosticLevel::Error | DiagnosticLevel::Ice | DiagnosticLevel::Warning => {
+                Some(item_diagnostic(&diagnostic))
+            }
+            _ => None,
+        };
         let Diagnostic {
             //message,
             //code,
@@ -122,6 +157,8 @@ impl CargoJsonAnalyzer {
                 DiagnosticLevel::Warning => LineType::Title(Kind::Warning),
                 _ => LineType::Normal,
             };
+            let mut title = None;
+            let mut location = None;
             for line in rendered.trim().lines() {
                 let content = TLine::from_tty(line);
                 command_output.push(CommandOutputLine {
@@ -134,13 +171,154 @@ impl CargoJsonAnalyzer {
                         line_type = LineType::Location;
                     }
                 }
+                let line = Line {
+                    item_idx: 0,
+                    line_type,
+                    content,
+                };
+                if title.is_none() {
+                    title = Some(line.content.to_raw());
+                }
+                if location.is_none() {
+                    location = line.location().map(String::from);
+                }
                 self.analysis
-                    .push((LineAnalysis::of_type(line_type), content));
+                    .push((LineAnalysis::of_type(line_type), line.content));
                 line_type = LineType::Normal;
             }
+            if let (Some(diagnostic), Some(title)) = (structured, title) {
+                let key = DiagnosticKey { title, location };
+                self.diagnostics.push((key, diagnostic));
+            }
         }
         for child in children {
             self.receive_diagnostic(child, origin, command_output);
         }
     }
 }
+
+/// Convert the diagnostic read from cargo into the structured
+/// diagnostic of a report item
+fn item_diagnostic(diagnostic: &Diagnostic) -> ItemDiagnostic {
+    ItemDiagnostic {
+        level: match diagnostic.level {
+            DiagnosticLevel::Ice => "internal compiler error",
+            DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+            DiagnosticLevel::FailureNote => "failure-note",
+            DiagnosticLevel::Note => "note",
+            DiagnosticLevel::Help => "help",
+            _ => "other",
+        }
+        .to_string(),
+        message: diagnostic.message.clone(),
+        code: diagnostic.code.as_ref().map(|code| code.code.clone()),
+        spans: diagnostic
+            .spans
+            .iter()
+            .map(|span| ItemSpan {
+                file_name: span.file_name.clone(),
+                byte_start: span.byte_start as usize,
+                byte_end: span.byte_end as usize,
+                line_start: span.line_start,
+                line_end: span.line_end,
+                column_start: span.column_start,
+                column_end: span.column_end,
+                text: span_text(&span.text),
+                is_primary: span.is_primary,
+                label: span.label.clone(),
+                suggested_replacement: span.suggested_replacement.clone(),
+                machine_applicable: span.suggestion_applicability
+                    == Some(Applicability::MachineApplicable),
+            })
+            .collect(),
+        children: diagnostic.children.iter().map(item_diagnostic).collect(),
+    }
+}
+
+/// Rebuild the source text of a span from the highlighted parts of its lines
+fn span_text(lines: &[DiagnosticSpanLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let start = line.highlight_start.saturating_sub(1);
+            let len = line.highlight_end.saturating_sub(1).saturating_sub(start);
+            line.text.chars().skip(start).take(len).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_diagnostic_pairing() {
+    let message = |level: &str, message: &str, rendered: &str| {
+        serde_json::json!({
+            "reason": "compiler-message",
+            "package_id": "path+file:///tmp/demo#0.1.0",
+            "target": {
+                "kind": ["bin"],
+                "crate_types": ["bin"],
+                "name": "demo",
+                "src_path": "/tmp/demo/src/main.rs",
+            },
+            "message": {
+                "message": message,
+                "code": null,
+                "level": level,
+                "spans": [],
+                "children": [],
+                "rendered": rendered,
+            },
+        })
+        .to_string()
+    };
+    let mut analyzer = CargoJsonAnalyzer::default();
+    let mut output = CommandOutput::default();
+    for line in [
+        message(
+            "warning",
+            "unused variable: `a`",
+            "warning: unused variable: `a`\n --> src/main.rs:2:9",
+        ),
+        // not displayed, so without item
+        message("error", "no rendering", ""),
+        message(
+            "error",
+            "mismatched types",
+            "error: mismatched types\n --> src/main.rs:4:5",
+        ),
+        message(
+            "warning",
+            "unused variable: `a`",
+            "warning: unused variable: `a`\n --> src/lib.rs:7:9",
+        ),
+        message(
+            "error",
+            "aborting due to 1 previous error",
+            "error: aborting due to 1 previous error",
+        ),
+    ] {
+        analyzer.receive_line(
+            CommandOutputLine {
+                content: TLine::from_tty(&line),
+                origin: CommandStream::StdOut,
+            },
+            &mut output,
+        );
+    }
+    let report = analyzer.build_report().unwrap();
+    assert_eq!(report.item_diagnostics.len(), 4);
+    for (item_idx, diagnostic) in &report.item_diagnostics {
+        let title = report
+            .lines
+            .iter()
+            .find(|line| line.item_idx == *item_idx)
+            .unwrap()
+            .content
+            .to_raw();
+        assert_eq!(
+            title,
+            format!("{}: {}", diagnostic.level, diagnostic.message)
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! An analyzer delegating the analysis to a helper program
+//!
+//! For every task, the helper is started and receives on its stdin the lines
+//! of the command output, one JSON object per line. It answers on its stdout
+//! either with one JSON `LineAnalysis` per received line, or, after its stdin
+//! is closed, with a whole report as a JSON object with a `lines` array.
+
+use {
+    super::standard,
+    crate::*,
+    anyhow::{
+        Result,
+        anyhow,
+        bail,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        io::{
+            self,
+            BufRead,
+            BufReader,
+            Read,
+            Write,
+        },
+        path::Path,
+        process::{
+            Child,
+            Command,
+            Stdio,
+        },
+        sync::{
+            Arc,
+            Mutex,
+        },
+        thread,
+        time::{
+            Duration,
+            Instant,
+        },
+    },
+    termimad::crossbeam::channel::{
+        Receiver,
+        Sender,
+        bounded,
+        unbounded,
+    },
+};
+
+/// How long the helper may take, after the end of the command's
+/// output, to answer and exit before being killed
+const HELPER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A line of the command output, as sent to the helper
+#[derive(Debug, Serialize)]
+struct LineMessage<'l> {
+    content: &'l str,
+    origin: CommandStream,
+}
+
+/// A report, as sent by the helper
+#[derive(Debug, Deserialize)]
+struct ReportMessage {
+    lines: Vec<Line>,
+    #[serde(default)]
+    failure_keys: Vec<String>,
+    #[serde(default)]
+    suggest_backtrace: bool,
+}
+
+/// The running helper program.
+///
+/// Its stdin, stdout and stderr are handled by dedicated threads, so
+/// that bacon never blocks on a helper which doesn't read or answer,
+/// and its end is awaited in another one.
+struct Helper {
+    child: Arc<Mutex<Child>>,
+    /// Sender of the JSON lines for the thread writing the helper's stdin,
+    /// which is closed when this sender is dropped
+    lines_tx: Option<Sender<String>>,
+    answers: Option<thread::JoinHandle<io::Result<Vec<String>>>>,
+    errors: Option<thread::JoinHandle<String>>,
+    /// Receiver of the answers, or of the error, once the helper ended
+    outcome: Option<Receiver<Result<Vec<String>>>>,
+}
+
+impl Drop for Helper {
+    fn drop(&mut self) {
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ExternalAnalyzer {
+    command: Vec<String>,
+    lines: Vec<CommandOutputLine>,
+    helper: Option<Result<Helper>>,
+}
+
+impl ExternalAnalyzer {
+    pub fn new(command: Vec<String>) -> Self {
+        Self {
+            command,
+            ..Default::default()
+        }
+    }
+    fn start_in(
+        &mut self,
+        dir: &Path,
+    ) {
+        self.lines.clear();
+        self.helper = None; // kills the previous helper if it's still running
+        self.helper = Some(spawn_helper(&self.command, dir));
+    }
+}
+
+fn spawn_helper(
+    command: &[String],
+    dir: &Path,
+) -> Result<Helper> {
+    let Some((exe, args)) = command.split_first() else {
+        bail!("Empty command for external analyzer");
+    };
+    let mut child = Command::new(exe)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to launch analyzer {exe:?}: {e}"))?;
+    let (Some(mut stdin), Some(stdout), Some(mut stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        bail!("Missing standard stream for analyzer {exe:?}");
+    };
+    let (lines_tx, lines_rx) = unbounded::<String>();
+    thread::spawn(move || {
+        for json in lines_rx {
+            if let Err(e) = writeln!(stdin, "{json}") {
+                warn!("failed to send line to analyzer: {e}");
+                break;
+            }
+        }
+        // stdin is dropped, hence closed, when the thread ends
+    });
+    // answers are read while lines are sent, so that no pipe fills
+    let answers = Some(thread::spawn(move || {
+        BufReader::new(stdout).lines().collect()
+    }));
+    let errors = Some(thread::spawn(move || {
+        let mut errors = String::new();
+        let _ = stderr.read_to_string(&mut errors);
+        errors
+    }));
+    Ok(Helper {
+        child: Arc::new(Mutex::new(child)),
+        lines_tx: Some(lines_tx),
+        answers,
+        errors,
+        outcome: None,
+    })
+}
+
+impl Helper {
+    /// Close the helper's stdin, telling it there's no more line, then
+    /// wait in a thread for its end. The returned receiver gets a message
+    /// when the outcome is ready.
+    fn close(&mut self) -> Receiver<()> {
+        self.lines_tx = None;
+        let (ready_tx, ready_rx) = bounded(1);
+        let (outcome_tx, outcome_rx) = bounded(1);
+        self.outcome = Some(outcome_rx);
+        let child = Arc::clone(&self.child);
+        let answers = self.answers.take();
+        let errors = self.errors.take();
+        thread::spawn(move || {
+            let _ = outcome_tx.send(wait_for_helper(&child, answers, errors));
+            let _ = ready_tx.send(());
+        });
+        ready_rx
+    }
+    /// Return the answers of the helper, or an error (with what the
+    /// helper wrote on stderr) if it failed or if it didn't finish in time
+    fn finish(&mut self) -> Result<Vec<String>> {
+        if self.outcome.is_none() {
+            self.close();
+        }
+        self.outcome
+            .take()
+            .and_then(|outcome| outcome.recv().ok())
+            .unwrap_or_else(|| Err(anyhow!("No answer from analyzer")))
+    }
+}
+
+/// Wait for the end of the helper and the reading of its output, killing
+/// it if it doesn't end within `HELPER_TIMEOUT`
+fn wait_for_helper(
+    child: &Mutex<Child>,
+    answers: Option<thread::JoinHandle<io::Result<Vec<String>>>>,
+    errors: Option<thread::JoinHandle<String>>,
+) -> Result<Vec<String>> {
+    let deadline = Instant::now() + HELPER_TIMEOUT;
+    let status = loop {
+        let answered = answers.as_ref().is_none_or(|a| a.is_finished());
+        let mut child = child
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock the analyzer"))?;
+        if answered {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+        }
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            bail!(
+                "External analyzer didn't finish within {}s",
+                HELPER_TIMEOUT.as_secs()
+            );
+        }
+        drop(child);
+        thread::sleep(Duration::from_millis(10));
+    };
+    let errors = errors
+        .and_then(|errors| errors.join().ok())
+        .unwrap_or_default();
+    if !status.success() {
+        let errors = errors.trim();
+        if errors.is_empty() {
+            bail!("External analyzer failed with {status}");
+        }
+        bail!("External analyzer failed with {status}: {errors}");
+    }
+    if !errors.trim().is_empty() {
+        debug!("analyzer stderr: {errors}");
+    }
+    answers
+        .ok_or_else(|| anyhow!("No answer from analyzer"))?
+        .join()
+        .map_err(|_| anyhow!("Failed to read the analyzer's answers"))?
+        .map_err(Into::into)
+}
+
+impl Analyzer for ExternalAnalyzer {
+    fn start(
+        &mut self,
+        mission: &Mission,
+    ) {
+        self.start_in(&mission.package_directory);
+    }
+
+    fn receive_line(
+        &mut self,
+        line: CommandOutputLine,
+        command_output: &mut CommandOutput,
+    ) {
+        if let Some(Ok(helper)) = &mut self.helper {
+            if let Some(lines_tx) = &helper.lines_tx {
+                let message = LineMessage {
+                    content: &line.content.to_raw(),
+                    origin: line.origin,
+                };
+                match serde_json::to_string(&message) {
+                    Ok(json) => {
+                        // fails only if the writing thread stopped, which it logged
+                        let _ = lines_tx.send(json);
+                    }
+                    Err(e) => {
+                        warn!("failed to serialize line for analyzer: {e}");
+                    }
+                }
+            }
+        }
+        self.lines.push(line.clone());
+        command_output.push(line);
+    }
+
+    fn end_of_output(&mut self) -> Option<Receiver<()>> {
+        match &mut self.helper {
+            Some(Ok(helper)) if helper.outcome.is_none() => Some(helper.close()),
+            _ => None,
+        }
+    }
+
+    fn build_report(&mut self) -> Result<Report> {
+        let mut helper = self
+            .helper
+            .take()
+            .ok_or_else(|| anyhow!("External analyzer not started"))??;
+        let answers = helper.finish()?;
+        report_from_answers(&self.lines, &answers)
+    }
+}
+
+/// Build the report either from the report sent by the helper or
+/// from the analysis it sent for every line
+fn report_from_answers(
+    lines: &[CommandOutputLine],
+    answers: &[String],
+) -> Result<Report> {
+    let answers: Vec<&str> = answers
+        .iter()
+        .map(|answer| answer.trim())
+        .filter(|answer| !answer.is_empty())
+        .collect();
+    if let Some(last) = answers.last() {
+        if let Ok(message) = serde_json::from_str::<ReportMessage>(last) {
+            let mut report = Report::new(message.lines);
+            report.failure_keys = message.failure_keys;
+            report.suggest_backtrace = message.suggest_backtrace;
+            return Ok(report);
+        }
+    }
+    if answers.len() != lines.len() {
+        warn!(
+            "analyzer sent {} answers for {} lines",
+            answers.len(),
+            lines.len()
+        );
+    }
+    let mut analyses = Vec::with_capacity(lines.len());
+    for answer in answers {
+        let analysis: LineAnalysis = serde_json::from_str(answer)
+            .map_err(|e| anyhow!("Invalid answer of analyzer: {e} in {answer:?}"))?;
+        analyses.push(analysis);
+    }
+    let analyses = analyses
+        .into_iter()
+        .chain(std::iter::repeat(LineAnalysis::normal()));
+    Ok(standard::build_report_from_analysis(
+        analyses.zip(lines.iter().map(|line| line.content.clone())),
+    ))
+}
+
+#[cfg(unix)]
+#[test]
+fn test_external_analyzer() {
+    let mut analyzer = ExternalAnalyzer::new(vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        r#"while read -r line; do
+            case "$line" in
+                *error:*) echo '{"line_type":{"Title":"Error"}}';;
+                *"-->"*) echo '{"line_type":"Location"}';;
+                *) echo '{"line_type":"Normal"}';;
+            esac
+        done"#
+            .to_string(),
+    ]);
+    analyzer.start_in(Path::new("."));
+    let mut output = CommandOutput::default();
+    for line in [
+        "Compiling",
+        "error: bad",
+        "  --> src/main.rs:2:3",
+        "",
+        "Done",
+    ] {
+        analyzer.receive_line(
+            CommandOutputLine {
+                content: TLine::from_raw(line.to_string()),
+                origin: CommandStream::StdErr,
+            },
+            &mut output,
+        );
+    }
+    let report = analyzer.build_report().unwrap();
+    assert_eq!(report.stats.errors, 1);
+    assert_eq!(report.item_location(1), Some("src/main.rs:2:3"));
+
+    // the helper may also send the whole report at end
+    let lines: Vec<CommandOutputLine> = Vec::new();
+    let answer = r#"{"lines":[{"item_idx":1,"line_type":{"Title":"Warning"},"content":{"strings":[{"csi":"","raw":"warning: meh"}]}}]}"#;
+    let report = report_from_answers(&lines, &[answer.to_string()]).unwrap();
+    assert_eq!(report.stats.warnings, 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_failing_external_analyzer() {
+    let mut analyzer = ExternalAnalyzer::new(vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        "echo 'no config found' >&2; exit 3".to_string(),
+    ]);
+    analyzer.start_in(Path::new("."));
+    // the end of the helper is awaited in a thread
+    let ready = analyzer.end_of_output().unwrap();
+    ready.recv().unwrap();
+    assert!(analyzer.end_of_output().is_none());
+    let error = analyzer.build_report().unwrap_err().to_string();
+    assert!(
+        error.ends_with("exit status: 3: no config found"),
+        "{error}"
+    );
+}
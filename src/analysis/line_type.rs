@@ -56,6 +56,10 @@ pub enum LineType {
         summary: bool,
     },
 
+    /// a line of the source code around a location, read by bacon
+    /// for the tools which don't display it
+    Snippet,
+
     /// any other line
     Normal,
 }
@@ -88,7 +92,7 @@ impl LineType {
     }
     pub fn is_summary(self) -> bool {
         match self {
-            Self::Normal | Self::Raw(_) => false,
+            Self::Normal | Self::Snippet | Self::Raw(_) => false,
             Self::Continuation { summary, .. } => summary,
             _ => true,
         }
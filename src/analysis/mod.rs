@@ -3,6 +3,7 @@ mod biome;
 mod cargo_json;
 mod cpp;
 mod eslint;
+mod external_analyzer;
 mod go;
 mod item_accumulator;
 mod line_analysis;
@@ -10,6 +11,7 @@ mod line_analyzer;
 mod line_type;
 mod nextest;
 mod python;
+mod regex_analyzer;
 mod standard;
 mod stats;
 mod swift;
@@ -17,9 +19,11 @@ mod typescript;
 
 pub use {
     analyzer::*,
+    external_analyzer::*,
     item_accumulator::*,
     line_analysis::*,
     line_analyzer::*,
     line_type::*,
+    regex_analyzer::*,
     stats::*,
 };
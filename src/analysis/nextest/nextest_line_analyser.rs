@@ -295,7 +295,7 @@ fn test_canceling() {
             TString::new("", " test still running"),
         ],
     };
-    assert_eq!(is_canceling(&content), true);
+    assert!(is_canceling(&content));
 }
 
 #[test]
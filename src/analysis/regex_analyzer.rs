@@ -0,0 +1,205 @@
+//! An analyzer defined in configuration, recognizing the lines of the
+//! command output with regular expressions
+
+use {
+    crate::*,
+    anyhow::Result,
+    lazy_regex::regex::Captures,
+    schemars::JsonSchema,
+    serde::Deserialize,
+};
+
+/// The definition of an analyzer, as found in an `[analyzers.name]`
+/// section of the configuration.
+///
+/// Patterns of titles and locations may have `path`, `line`, and `column`
+/// named groups, used to build the location of the item. Title patterns
+/// may also have a `message` named group.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, JsonSchema)]
+pub struct RegexAnalyzerConfig {
+    /// Patterns of the lines starting an error
+    #[serde(default)]
+    pub errors: Vec<LinePattern>,
+
+    /// Patterns of the lines starting a warning
+    #[serde(default)]
+    pub warnings: Vec<LinePattern>,
+
+    /// Patterns of the lines starting a test failure
+    #[serde(default)]
+    pub test_failures: Vec<LinePattern>,
+
+    /// Patterns of the lines locating the current item
+    #[serde(default)]
+    pub locations: Vec<LinePattern>,
+
+    /// Patterns of the lines ending the current item (they're
+    /// not part of the item)
+    #[serde(default)]
+    pub item_ends: Vec<LinePattern>,
+
+    /// Patterns of the lines to remove from the report
+    #[serde(default)]
+    pub garbage_lines: Vec<LinePattern>,
+}
+
+#[derive(Debug, Default)]
+pub struct RegexAnalyzer {
+    config: RegexAnalyzerConfig,
+    lines: Vec<CommandOutputLine>,
+}
+
+impl RegexAnalyzer {
+    pub fn new(config: RegexAnalyzerConfig) -> Self {
+        Self {
+            config,
+            lines: Vec::new(),
+        }
+    }
+}
+
+impl Analyzer for RegexAnalyzer {
+    fn start(
+        &mut self,
+        _: &Mission,
+    ) {
+        self.lines.clear();
+    }
+
+    fn receive_line(
+        &mut self,
+        line: CommandOutputLine,
+        command_output: &mut CommandOutput,
+    ) {
+        self.lines.push(line.clone());
+        command_output.push(line);
+    }
+
+    fn build_report(&mut self) -> Result<Report> {
+        Ok(build_report(&self.config, &self.lines))
+    }
+}
+
+fn captures<'r>(
+    patterns: &[LinePattern],
+    raw: &'r str,
+) -> Option<Captures<'r>> {
+    patterns.iter().find_map(|p| p.regex.captures(raw))
+}
+
+fn is_match(
+    patterns: &[LinePattern],
+    raw: &str,
+) -> bool {
+    patterns.iter().any(|p| p.raw_line_is_match(raw))
+}
+
+/// Build the location (eg `src/main.c:12:5`) from the `path`, `line`,
+/// and `column` groups of the captures
+fn captured_location(caps: &Captures) -> Option<String> {
+    let mut location = caps.name("path")?.as_str().to_string();
+    for name in ["line", "column"] {
+        if let Some(m) = caps.name(name) {
+            location.push(':');
+            location.push_str(m.as_str());
+        }
+    }
+    Some(location)
+}
+
+fn build_report(
+    config: &RegexAnalyzerConfig,
+    cmd_lines: &[CommandOutputLine],
+) -> Report {
+    let mut items = ItemAccumulator::default();
+    for cmd_line in cmd_lines {
+        let raw = cmd_line.content.to_raw();
+        if is_match(&config.garbage_lines, &raw) {
+            continue;
+        }
+        if is_match(&config.item_ends, &raw) {
+            items.close_item();
+            continue;
+        }
+        let title = [
+            (Kind::Error, &config.errors),
+            (Kind::Warning, &config.warnings),
+            (Kind::TestFail, &config.test_failures),
+        ]
+        .into_iter()
+        .find_map(|(kind, patterns)| captures(patterns, &raw).map(|caps| (kind, caps)));
+        if let Some((kind, caps)) = title {
+            let content = match caps.name("message").map(|m| m.as_str()) {
+                Some(message) => match kind {
+                    Kind::Error => burp::error_line(message),
+                    Kind::Warning => burp::warning_line_ts(&[TString::new("", message)]),
+                    _ => burp::failure_line(message),
+                },
+                None => cmd_line.content.clone(),
+            };
+            items.start_item(kind);
+            items.push_line(LineType::Title(kind), content);
+            if let Some(location) = captured_location(&caps) {
+                items.push_line(LineType::Location, burp::location_line(location));
+            }
+            continue;
+        }
+        if let Some(caps) = captures(&config.locations, &raw) {
+            let location = captured_location(&caps).unwrap_or_else(|| caps[0].trim().to_string());
+            items.push_line(LineType::Location, burp::location_line(location));
+            continue;
+        }
+        items.push_line(LineType::Normal, cmd_line.content.clone());
+    }
+    items.report()
+}
+
+#[test]
+fn test_regex_analyzer() {
+    let config: RegexAnalyzerConfig = toml::from_str(
+        r#"
+        errors = ['^(?P<path>\S+):(?P<line>\d+): E\d+ (?P<message>.*)$']
+        warnings = ['^(?P<path>\S+):(?P<line>\d+):(?P<column>\d+): W\d+ (?P<message>.*)$']
+        test_failures = ['^FAILED ']
+        locations = ['^\s+at (?P<path>\S+) line (?P<line>\d+)$']
+        item_ends = ['^---']
+        garbage_lines = ['^Checking ']
+        "#,
+    )
+    .unwrap();
+    let output = "\
+Checking 3 files
+src/a.py:3: E101 undefined name
+    detail of the error
+---
+outside of any item
+src/b.py:7:12: W200 line too long
+FAILED test_thing
+    at tests/test_a.py line 42
+";
+    let lines: Vec<CommandOutputLine> = output
+        .lines()
+        .map(|line| CommandOutputLine {
+            content: TLine::from_raw(line.to_string()),
+            origin: CommandStream::StdOut,
+        })
+        .collect();
+    let report = build_report(&config, &lines);
+    assert_eq!(report.stats.errors, 1);
+    assert_eq!(report.stats.warnings, 1);
+    assert_eq!(report.stats.test_fails, 1);
+    let raw_lines: Vec<String> = report.lines.iter().map(|l| l.content.to_raw()).collect();
+    assert_eq!(
+        raw_lines,
+        vec![
+            "error: undefined name",
+            "   --> src/a.py:3",
+            "    detail of the error",
+            "FAILED test_thing",
+            "   --> tests/test_a.py:42",
+            "warning: line too long",
+            "   --> src/b.py:7:12",
+        ],
+    );
+    assert_eq!(report.item_location(3), Some("src/b.py:7:12"));
+}
@@ -70,7 +70,7 @@ fn analyze_line(cmd_line: &CommandOutputLine) -> LineAnalysis {
             LineType::Normal
         }
     } else {
-        let ts0 = content.strings.get(0);
+        let ts0 = content.strings.first();
         let ts1 = content.strings.get(1);
         match (ts0, ts1) {
             (Some(title), Some(body)) => {
@@ -25,7 +25,7 @@ pub fn build_report_from_analysis(lines: impl Iterator<Item = (LineAnalysis, TLi
     let mut errors = Vec::new();
     let mut fails = Vec::new();
     let mut tests: FxHashMap<String, Test> = Default::default();
-    let mut passed_tests = 0;
+    let mut passed_tests = Vec::new();
     let mut cur_err_kind = None; // the current kind among stderr lines
     let mut is_in_out_fail = false;
     let mut suggest_backtrace = false;
@@ -39,7 +39,7 @@ pub fn build_report_from_analysis(lines: impl Iterator<Item = (LineAnalysis, TLi
         match (line_type, line_analysis.key) {
             (LineType::TestResult(r), Some(key)) => {
                 if r {
-                    passed_tests += 1;
+                    passed_tests.push(key.clone());
                     tests.insert(
                         key,
                         Test {
@@ -47,10 +47,10 @@ pub fn build_report_from_analysis(lines: impl Iterator<Item = (LineAnalysis, TLi
                             has_title: false,
                         },
                     );
-                } else if !tests.contains_key(&key) {
+                } else {
                     // we should receive the test test section later,
                     // right now we just whitelist it
-                    tests.insert(key, Test::default());
+                    tests.entry(key).or_default();
                 }
             }
             (LineType::Title(Kind::TestOutput | Kind::TestFail), Some(key)) => {
@@ -155,10 +155,11 @@ pub fn build_report_from_analysis(lines: impl Iterator<Item = (LineAnalysis, TLi
 
     let mut report = Report::new(lines);
     report.suggest_backtrace = suggest_backtrace;
-    report.has_passed_tests = passed_tests > 0;
+    report.has_passed_tests = !passed_tests.is_empty();
     report.failure_keys = tests
         .drain()
         .filter_map(|(key, test)| if !test.passed { Some(key) } else { None })
         .collect::<Vec<_>>();
+    report.passed_tests = passed_tests;
     report
 }
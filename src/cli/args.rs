@@ -111,6 +111,23 @@ pub struct Args {
     #[clap(long)]
     pub send: Option<String>,
 
+    /// With --send, wait for the end of the triggered task, print its
+    /// result, and exit with an error code if it failed
+    #[cfg(unix)]
+    #[clap(long, requires = "send")]
+    pub wait: bool,
+
+    /// With --wait, print the result as JSON instead of badges
+    #[cfg(unix)]
+    #[clap(long, requires = "wait")]
+    pub json: bool,
+
+    /// Run a Language Server on stdio, publishing the diagnostics of the
+    /// listening bacon, or of a headless one launched for the purpose
+    #[cfg(unix)]
+    #[clap(long)]
+    pub lsp: bool,
+
     /// Job to launch: `check`, `clippy`, custom ones...
     #[clap(short = 'j', long, value_name = "job", add = ArgValueCandidates::new(crate::cli::completions::list_jobs))]
     pub job: Option<ConcreteJobRef>,
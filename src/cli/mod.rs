@@ -18,6 +18,10 @@ use {
     termimad::crossterm::{
         QueueableCommand,
         cursor,
+        event::{
+            DisableMouseCapture,
+            EnableMouseCapture,
+        },
         terminal::{
             EnterAlternateScreen,
             LeaveAlternateScreen,
@@ -25,12 +29,6 @@ use {
     },
 };
 
-#[cfg(windows)]
-use termimad::crossterm::event::{
-    DisableMouseCapture,
-    EnableMouseCapture,
-};
-
 /// The Write type used by all GUI writing functions
 pub type W = std::io::BufWriter<std::io::Stdout>;
 
@@ -102,10 +100,32 @@ pub fn run() -> anyhow::Result<()> {
 
     #[cfg(unix)]
     if let Some(action) = &args.send {
-        socket::send_action(&context, action)?;
+        if !args.wait {
+            socket::send_action(&context, action)?;
+            return Ok(());
+        }
+        let status = socket::send_action_and_wait(&context, action)?;
+        let mut w = writer();
+        if args.json {
+            writeln!(w, "{}", serde_json::to_string(&status)?)?;
+        } else {
+            for badge in &status.badges {
+                badge.draw(&mut w)?;
+            }
+            writeln!(w)?;
+        }
+        w.flush()?;
+        if status.failure {
+            std::process::exit(1);
+        }
         return Ok(());
     }
 
+    #[cfg(unix)]
+    if args.lsp {
+        return lsp::run_lsp(&context);
+    }
+
     let settings = Settings::read(&args, &context)?;
 
     if args.list_jobs {
@@ -125,13 +145,14 @@ pub fn run() -> anyhow::Result<()> {
     if !headless {
         w.queue(EnterAlternateScreen)?;
         w.queue(cursor::Hide)?;
-        #[cfg(windows)]
-        w.queue(EnableMouseCapture)?;
+        if settings.mouse_capture {
+            w.queue(EnableMouseCapture)?;
+        }
         w.flush()?;
     }
     let result = tui::app::run(&mut w, settings, &args, &context, headless);
     if !headless {
-        #[cfg(windows)]
+        // the capture may have been enabled by a reload of the configuration
         w.queue(DisableMouseCapture)?;
         w.queue(cursor::Show)?;
         w.queue(LeaveAlternateScreen)?;
@@ -25,13 +25,17 @@ use {
 ///  or sent from another program.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Action {
+    ApplyFix,   // apply the machine applicable suggestions of the top item
     Back,       // leave help, clear search, go to previous job, leave, etc.
     BackOrQuit, // same as Back but quits if there is nothing to go back to
     ClearOutput,
+    CopyItem,
     CopyUnstyledOutput,
+    DismissMatchingItems,
     DismissTop,
     DismissTopItem,
     DismissTopItemType,
+    Explain,
     Export(String),
     FocusFile(FocusFileCommand),
     FocusGoto,
@@ -40,6 +44,7 @@ pub enum Action {
     Job(JobRef),
     NextMatch,
     NoOp, // no operation, can be used to clear a binding
+    OpenInEditor,
     OpenJobsMenu,
     OpenMenu(Box<ActionMenuDefinition>),
     OpenUndismissMenu,
@@ -47,20 +52,32 @@ pub enum Action {
     PlaySound(PlaySoundCommand),
     PreviousMatch,
     Quit,
+    RecordBaseline,
     ReRun,
     Refresh, // clear and rerun
     ReloadConfig,
     ScopeToFailures,
     Scroll(ScrollCommand),
+    SelectNextItem,
+    SelectPreviousItem,
+    Shell(String),
     ShowItem(ShowItemCommand),
     ToggleBacktrace(&'static str),
+    ToggleNewOnly,
     TogglePause, // either pause or unpause
     ToggleRawOutput,
+    ToggleSearchCaseInsensitive,
+    ToggleSearchFilter,
+    ToggleSearchRegex,
+    ToggleSearchWholeWord,
     ToggleSummary,
     ToggleWrap,
     UndismissAll,
+    UndismissCombined(CombinedDismissal),
     UndismissDiagType(String),
+    UndismissFileGlob(String),
     UndismissLocation(String),
+    UndismissTitleRegex(String),
     Unpause,
     Validate, // validate search entry
 }
@@ -69,15 +86,19 @@ impl Md for Action {
     /// Return the action description to show in doc/help
     fn md(&self) -> String {
         match self {
+            Self::ApplyFix => "apply the suggested fix of the top item".to_string(),
             Self::Back => "back to previous page or job".to_string(),
             Self::BackOrQuit => {
                 "back to previous page or job, quitting if there is none".to_string()
             }
             Self::ClearOutput => "clear output".to_string(),
+            Self::CopyItem => "copy the current item".to_string(),
             Self::CopyUnstyledOutput => "copy unstyled output".to_string(),
+            Self::DismissMatchingItems => "dismiss the items matching the search".to_string(),
             Self::DismissTop => "dismiss top".to_string(),
             Self::DismissTopItem => "dismiss top item".to_string(),
             Self::DismissTopItemType => "dismiss top item type".to_string(),
+            Self::Explain => "explain the error code of the top item".to_string(),
             Self::Export(export_name) => format!("run *{export_name}* export"),
             Self::FocusFile(fc) => fc.doc(),
             Self::FocusGoto => "focus goto".to_string(),
@@ -86,21 +107,33 @@ impl Md for Action {
             Self::Job(job_name) => format!("*{job_name}* job"),
             Self::NextMatch => "next match".to_string(),
             Self::NoOp => "no operation".to_string(),
+            Self::OpenInEditor => "open top item in editor".to_string(),
             Self::OpenMenu(_) => "open specific menu".to_string(),
             Self::OpenJobsMenu => "open jobs menu".to_string(),
             Self::Pause => "pause".to_string(),
             Self::PlaySound(_) => "play sound".to_string(),
             Self::PreviousMatch => "previous match".to_string(),
             Self::Quit => "quit".to_string(),
+            Self::RecordBaseline => "record the items in the job's baseline".to_string(),
             Self::ReRun => "run current job again".to_string(),
             Self::Refresh => "clear then run current job again".to_string(),
             Self::ReloadConfig => "reload configuration files".to_string(),
             Self::ScopeToFailures => "scope to failures".to_string(),
             Self::Scroll(scroll_command) => scroll_command.doc(),
+            Self::SelectNextItem => "select next item".to_string(),
+            Self::SelectPreviousItem => "select previous item".to_string(),
+            Self::Shell(command) => format!("run `{command}`"),
             Self::ShowItem(sic) => sic.doc(),
             Self::ToggleBacktrace(level) => format!("toggle backtrace ({level})"),
+            Self::ToggleNewOnly => "toggle showing only new items".to_string(),
             Self::TogglePause => "toggle pause".to_string(),
             Self::ToggleRawOutput => "toggle raw output".to_string(),
+            Self::ToggleSearchCaseInsensitive => "toggle case insensitive search".to_string(),
+            Self::ToggleSearchFilter => {
+                "toggle showing only the items matching the search".to_string()
+            }
+            Self::ToggleSearchRegex => "toggle regex search".to_string(),
+            Self::ToggleSearchWholeWord => "toggle whole word search".to_string(),
             Self::ToggleSummary => "toggle summary".to_string(),
             Self::ToggleWrap => "toggle wrap".to_string(),
             Self::Unpause => "unpause".to_string(),
@@ -108,6 +141,9 @@ impl Md for Action {
             Self::UndismissAll => "undismiss everything".to_string(),
             Self::UndismissLocation(loc) => format!("undismiss *{loc}*"),
             Self::UndismissDiagType(dt) => format!("undismiss *{dt}*"),
+            Self::UndismissTitleRegex(regex) => format!("undismiss titles matching `{regex}`"),
+            Self::UndismissFileGlob(glob) => format!("undismiss files matching `{glob}`"),
+            Self::UndismissCombined(combined) => format!("undismiss *{combined}*"),
             Self::OpenUndismissMenu => "open undismiss menu".to_string(),
         }
     }
@@ -116,6 +152,7 @@ impl Md for Action {
 #[derive(Debug, PartialEq)]
 pub enum ParseActionError {
     InvalidBacktraceLevel(String),
+    InvalidDismissal(String),
     InvalidPlaySoundParameter(String),
     InvalidScrollCommand(String),
     InvalidVolume(ParseVolumeError),
@@ -132,6 +169,9 @@ impl fmt::Display for ParseActionError {
             Self::InvalidBacktraceLevel(level) => {
                 write!(f, "Invalid backtrace level: {level:?}")
             }
+            Self::InvalidDismissal(dismissal) => {
+                write!(f, "Invalid dismissal: {dismissal:?}")
+            }
             Self::InvalidPlaySoundParameter(param) => {
                 write!(f, "Invalid play sound parameter: {param:?}")
             }
@@ -167,13 +207,17 @@ impl fmt::Display for Action {
         f: &mut fmt::Formatter,
     ) -> fmt::Result {
         match self {
+            Self::ApplyFix => write!(f, "apply-fix"),
             Self::Back => write!(f, "back"),
             Self::BackOrQuit => write!(f, "back-or-quit"),
             Self::ClearOutput => write!(f, "clear-output"),
+            Self::CopyItem => write!(f, "copy-item"),
             Self::CopyUnstyledOutput => write!(f, "copy-unstyled-output"),
+            Self::DismissMatchingItems => write!(f, "dismiss-matching-items"),
             Self::DismissTop => write!(f, "dismiss-top"),
             Self::DismissTopItem => write!(f, "dismiss-top-item"),
             Self::DismissTopItemType => write!(f, "dismiss-top-item-type"),
+            Self::Explain => write!(f, "explain"),
             Self::Export(name) => write!(f, "export:{name}"),
             Self::FocusFile(FocusFileCommand { file }) => {
                 write!(f, "focus-file({file})")
@@ -184,6 +228,7 @@ impl fmt::Display for Action {
             Self::Job(job_ref) => write!(f, "job:{job_ref}"),
             Self::NextMatch => write!(f, "next-match"),
             Self::NoOp => write!(f, "no-op"),
+            Self::OpenInEditor => write!(f, "open-in-editor"),
             Self::OpenJobsMenu => write!(f, "open-jobs-menu"),
             Self::OpenMenu(def) => {
                 write!(f, "open-menu(")?;
@@ -210,22 +255,37 @@ impl fmt::Display for Action {
             }
             Self::PreviousMatch => write!(f, "previous-match"),
             Self::Quit => write!(f, "quit"),
+            Self::RecordBaseline => write!(f, "record-baseline"),
             Self::ReRun => write!(f, "rerun"),
             Self::Refresh => write!(f, "refresh"),
             Self::ReloadConfig => write!(f, "reload-config"),
             Self::ScopeToFailures => write!(f, "scope-to-failures"),
             Self::Scroll(scroll_command) => scroll_command.fmt(f),
+            Self::SelectNextItem => write!(f, "select-next-item"),
+            Self::SelectPreviousItem => write!(f, "select-previous-item"),
+            Self::Shell(command) => write!(f, "shell:{command}"),
             Self::ShowItem(ShowItemCommand { item_idx }) => {
                 write!(f, "show-item({item_idx})")
             }
             Self::ToggleBacktrace(level) => write!(f, "toggle-backtrace({level})"),
+            Self::ToggleNewOnly => write!(f, "toggle-new-only"),
             Self::TogglePause => write!(f, "toggle-pause"),
             Self::ToggleRawOutput => write!(f, "toggle-raw-output"),
+            Self::ToggleSearchCaseInsensitive => write!(f, "toggle-search-case-insensitive"),
+            Self::ToggleSearchFilter => write!(f, "toggle-search-filter"),
+            Self::ToggleSearchRegex => write!(f, "toggle-search-regex"),
+            Self::ToggleSearchWholeWord => write!(f, "toggle-search-whole-word"),
             Self::ToggleSummary => write!(f, "toggle-summary"),
             Self::ToggleWrap => write!(f, "toggle-wrap"),
             Self::UndismissAll => write!(f, "undismiss-all"),
             Self::UndismissLocation(loc) => write!(f, "undismiss-location({loc})"),
             Self::UndismissDiagType(dt) => write!(f, "undismiss-diag-type({dt})"),
+            Self::UndismissTitleRegex(regex) => write!(f, "undismiss-title-regex({regex})"),
+            Self::UndismissFileGlob(glob) => write!(f, "undismiss-file-glob({glob})"),
+            Self::UndismissCombined(combined) => {
+                let json = serde_json::to_string(combined).map_err(|_| fmt::Error)?;
+                write!(f, "undismiss-combined({json})")
+            }
             Self::Unpause => write!(f, "unpause"),
             Self::Validate => write!(f, "validate"),
         }
@@ -237,12 +297,16 @@ impl FromStr for Action {
         regex_switch!(s,
             r"^export:(?<name>.+)$" => Self::Export(name.to_string()),
             r"^job:(?<job_ref>.+)$" => Self::Job(job_ref.into()),
+            r"^shell:(?<command>.+)$" => Self::Shell(command.trim().to_string()),
+            r"^(?:internal:)?apply-fix$" => Self::ApplyFix,
             r"^(?:internal:)?back$" => Self::Back,
             r"^(?:internal:)?back-or-quit$" => Self::BackOrQuit,
             r"^(?:internal:)?clear-output$" => Self::ClearOutput,
+            r"^(?:internal:)?dismiss-matching-items$" => Self::DismissMatchingItems,
             r"^(?:internal:)?dismiss-top$" => Self::DismissTop,
             r"^(?:internal:)?dismiss-top-item$" => Self::DismissTopItem,
             r"^(?:internal:)?dismiss-top-item-type$" => Self::DismissTopItemType,
+            r"^(?:internal:)?explain$" => Self::Explain,
             r"^(?:internal:)?help$" => Self::Help,
             r"^(?:internal:)?quit$" => Self::Quit,
             r"^(?:internal:)?open-menu\((?:intro=(?<intro>.+),\s*)?(?:actions=\[(?<actions>.+)\])\)$" => {
@@ -257,10 +321,14 @@ impl FromStr for Action {
                 }))
             }
             r"^open-jobs?-menu$" => Self::OpenJobsMenu,
+            r"^(?:internal:)?open-in-editor$" => Self::OpenInEditor,
+            r"^(?:internal:)?record-baseline$" => Self::RecordBaseline,
             r"^(?:internal:)?refresh$" => Self::Refresh,
             r"^(?:internal:)?reload-config$" => Self::ReloadConfig,
             r"^(?:internal:)?rerun$" => Self::ReRun,
             r"^(?:internal:)?scope-to-failures$" => Self::ScopeToFailures,
+            r"^(?:internal:)?select-next-item$" => Self::SelectNextItem,
+            r"^(?:internal:)?select-previous-item$" => Self::SelectPreviousItem,
             r"^(?:internal:)?toggle-raw-output$" => Self::ToggleRawOutput,
             r"^(?:internal:)?toggle-backtrace$" => Self::ToggleBacktrace("1"),
             r"^(?:internal:)?toggle-backtrace\(\s*(?<level>.+)\s*\)$" => {
@@ -275,7 +343,12 @@ impl FromStr for Action {
                 };
                 Self::ToggleBacktrace(level)
             }
+            r"^(?:internal:)?toggle-search-case-insensitive$" => Self::ToggleSearchCaseInsensitive,
+            r"^(?:internal:)?toggle-search-filter$" => Self::ToggleSearchFilter,
+            r"^(?:internal:)?toggle-search-regex$" => Self::ToggleSearchRegex,
+            r"^(?:internal:)?toggle-search-whole-word$" => Self::ToggleSearchWholeWord,
             r"^(?:internal:)?toggle-summary$" => Self::ToggleSummary,
+            r"^(?:internal:)?toggle-new-only$" => Self::ToggleNewOnly,
             r"^(?:internal:)?toggle-wrap$" => Self::ToggleWrap,
             r"^(?:internal:)?(noop|no-op|no-operation)$" => Self::NoOp,
             r"^(?:internal:)?pause$" => Self::Pause,
@@ -289,7 +362,14 @@ impl FromStr for Action {
             r"^(?:internal:)?undismiss-all$" => Self::UndismissAll,
             r"^(?:internal:)?undismiss-location\((?<location>.+)\)$" => Self::UndismissLocation(location.to_string()),
             r"^(?:internal:)?undismiss-diag-type\((?<diag_type>.+)\)$" => Self::UndismissDiagType(diag_type.to_string()),
+            r"^(?:internal:)?undismiss-title-regex\((?<regex>.+)\)$" => Self::UndismissTitleRegex(regex.to_string()),
+            r"^(?:internal:)?undismiss-file-glob\((?<glob>.+)\)$" => Self::UndismissFileGlob(glob.to_string()),
+            r"^(?:internal:)?undismiss-combined\((?<json>.+)\)$" => Self::UndismissCombined(
+                serde_json::from_str(json)
+                    .map_err(|_| ParseActionError::InvalidDismissal(json.to_string()))?,
+            ),
             r"^(?:internal:)?open-undismiss-menu$" => Self::OpenUndismissMenu,
+            r"^(?:internal:)?copy-item$" => Self::CopyItem,
             r"^(?:internal:)?copy-unstyled-output$" => Self::CopyUnstyledOutput,
             r"^(?:internal:)?play-sound$" => Self::PlaySound(PlaySoundCommand::default()),
             r"^(?:internal:)?play-sound\((?<props>.*)\)$" => {
@@ -412,15 +492,34 @@ fn test_action_string_round_trip() {
         Action::Scroll(ScrollCommand::MilliPages(-500)),
         Action::Scroll(ScrollCommand::MilliPages(-2000)),
         Action::Export("my export".to_string()),
+        Action::Shell("git add {file} && echo done".to_string()),
         Action::Back,
         Action::BackOrQuit,
         Action::ClearOutput,
+        Action::DismissMatchingItems,
         Action::DismissTop,
         Action::DismissTopItem,
         Action::DismissTopItemType,
         Action::UndismissAll,
         Action::UndismissLocation("src/main.rs:42".to_string()),
+        Action::UndismissTitleRegex("^warning: field .* is never read$".to_string()),
+        Action::UndismissFileGlob("src/generated/**".to_string()),
+        Action::UndismissCombined(CombinedDismissal {
+            diag_type: Some("dead_code".to_string()),
+            file_glob: Some("src/generated/**".to_string()),
+            title_regex: None,
+        }),
         Action::FocusSearch,
+        Action::OpenInEditor,
+        Action::ApplyFix,
+        Action::CopyItem,
+        Action::Explain,
+        Action::SelectNextItem,
+        Action::SelectPreviousItem,
+        Action::ToggleSearchCaseInsensitive,
+        Action::ToggleSearchFilter,
+        Action::ToggleSearchRegex,
+        Action::ToggleSearchWholeWord,
         Action::OpenJobsMenu,
         Action::OpenMenu(Box::new(ActionMenuDefinition {
             intro: Some("This is a menu".to_string()),
@@ -439,6 +538,7 @@ fn test_action_string_round_trip() {
         Action::Pause,
         Action::Quit,
         Action::ReRun,
+        Action::RecordBaseline,
         Action::ReloadConfig,
         Action::ScopeToFailures,
         Action::Scroll(ScrollCommand::MilliPages(-3000)),
@@ -448,6 +548,7 @@ fn test_action_string_round_trip() {
         Action::ToggleBacktrace("1"),
         Action::ToggleBacktrace("full"),
         Action::TogglePause,
+        Action::ToggleNewOnly,
         Action::ToggleSummary,
         Action::ToggleWrap,
         Action::Unpause,
@@ -473,7 +574,7 @@ fn test_action_string_round_trip() {
         Action::FocusFile(FocusFileCommand::new("src/main.rs")),
     ];
     for action in actions {
-        println!("action: {}", action.to_string());
+        println!("action: {}", action);
         assert_eq!(action.to_string().parse(), Ok(action));
     }
 }
@@ -1,4 +1,10 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AutoRefresh {
     /// Don't rerun the job on file changes.
     Paused,
@@ -25,6 +25,18 @@ pub struct Config {
     /// Extra arguments appended when a job runs a cargo alias.
     pub additional_alias_args: Option<Vec<String>>,
 
+    /// Analyzers defined with regular expressions, keyed by the
+    /// name jobs use in their `analyzer` property
+    #[serde(default)]
+    pub analyzers: HashMap<String, RegexAnalyzerConfig>,
+
+    /// Jobs to keep running in the background, shown in a dashboard
+    /// whatever the displayed job.
+    pub concurrent_jobs: Option<Vec<ConcreteJobRef>>,
+
+    /// Items to always dismiss, eg `[{ diag_type = "dead_code" }]`.
+    pub dismissals: Option<Vec<Dismissal>>,
+
     /// Name of the job to run when no job was requested explicitly.
     pub default_job: Option<ConcreteJobRef>,
 
@@ -40,6 +52,11 @@ pub struct Config {
     #[deprecated(since = "2.9.0", note = "use exports.locations.auto")]
     pub export_locations: Option<bool>,
 
+    /// Command opening a file in the editor, eg `"code --goto {file}:{line}:{column}"`.
+    ///
+    /// Default is `$EDITOR +{line} {file}`.
+    pub editor: Option<String>,
+
     /// Export configurations keyed by their name.
     #[serde(default)]
     pub exports: HashMap<String, ExportConfig>,
@@ -68,6 +85,20 @@ pub struct Config {
     /// Whether to listen for actions on a unix socket (if on unix)
     pub listen: Option<bool>,
 
+    /// Whether to capture the mouse, for wheel scrolling and clicks.
+    ///
+    /// Default is true. Without capture, the terminal handles text selection.
+    pub mouse_capture: Option<bool>,
+
+    /// Action to run on a click on a location, eg `"open-in-editor"` (the default).
+    pub on_location_click: Option<Action>,
+
+    /// Whether to display the source lines around the locations, for the
+    /// analyzers of tools which don't print them (eg eslint or ruff).
+    ///
+    /// Default is true.
+    pub source_snippets: Option<bool>,
+
     /// Whether to wrap long lines
     pub wrap: Option<bool>,
 }
@@ -97,7 +128,7 @@ impl Config {
             if !regex_is_match!(r#"^[\w-]+$"#, name) {
                 bail!("Invalid configuration : Illegal job name : {name:?}");
             }
-            if job.command.is_empty() {
+            if job.command.is_empty() && job.extends.is_none() {
                 bail!("Invalid configuration : empty command for job {name:?}");
             }
         }
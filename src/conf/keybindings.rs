@@ -62,6 +62,17 @@ impl Default for KeyBindings {
         bindings.set(key!(tab), Action::NextMatch);
         bindings.set(key!(backtab), Action::PreviousMatch);
         bindings.set(key!(ctrl - j), Action::OpenJobsMenu);
+        bindings.set(key!(o), Action::OpenInEditor);
+        bindings.set(key!(alt - f), Action::ApplyFix);
+        bindings.set(key!(e), Action::Explain);
+        bindings.set(key!(y), Action::CopyItem);
+        bindings.set(key!(alt - n), Action::ToggleNewOnly);
+        bindings.set(key!(alt - down), Action::SelectNextItem);
+        bindings.set(key!(alt - up), Action::SelectPreviousItem);
+        bindings.set(key!(alt - r), Action::ToggleSearchRegex);
+        bindings.set(key!(alt - c), Action::ToggleSearchCaseInsensitive);
+        bindings.set(key!(alt - w), Action::ToggleSearchWholeWord);
+        bindings.set(key!(ctrl - f), Action::ToggleSearchFilter);
 
         // keybindings for some common jobs
         bindings.set(key!(a), JobRef::from_job_name("check-all"));
@@ -18,18 +18,32 @@ pub struct Settings {
     pub additional_alias_args: Option<Vec<String>>,
     pub additional_job_args: Vec<String>,
     pub all_features: bool,
+    /// Analyzers defined in configuration
+    pub analyzers: HashMap<String, RegexAnalyzerConfig>,
     pub arg_job: Option<ConcreteJobRef>,
+    /// Jobs run in the background and shown in the dashboard
+    pub concurrent_jobs: Vec<ConcreteJobRef>,
     /// Path of the files which were used to build the settings
     /// (note that not all settings come from files)
     pub config_files: Vec<PathBuf>,
     pub default_job: ConcreteJobRef,
+    /// Dismissals declared in configuration
+    pub dismissals: Vec<Dismissal>,
+    /// Command template of the `open-in-editor` action
+    pub editor: Option<String>,
     pub exports: ExportsSettings,
     pub features: Option<String>, // comma separated list
     pub help_line: bool,
     pub jobs: HashMap<String, Job>,
     pub keybindings: KeyBindings,
+    /// Whether to capture the mouse
+    pub mouse_capture: bool,
     pub no_default_features: bool,
+    /// Action run on a click on a location line
+    pub on_location_click: Action,
     pub reverse: bool,
+    /// Whether to display the source around locations, when the tool doesn't
+    pub source_snippets: bool,
     pub summary: bool,
     pub wrap: bool,
     /// Whether to listen for actions on a unix socket (if on unix)
@@ -49,10 +63,17 @@ impl Default for Settings {
             help_line: true,
             no_default_features: Default::default(),
             all_features: Default::default(),
+            analyzers: Default::default(),
             features: Default::default(),
             keybindings: Default::default(),
+            mouse_capture: true,
+            on_location_click: Action::OpenInEditor,
+            source_snippets: true,
             jobs: Default::default(),
             default_job: Default::default(),
+            dismissals: Default::default(),
+            concurrent_jobs: Default::default(),
+            editor: Default::default(),
             exports: Default::default(),
             config_files: Default::default(),
             listen: false,
@@ -116,6 +137,7 @@ impl Settings {
             settings.apply_config(&config);
         }
 
+        resolve_job_extensions(&mut settings.jobs)?;
         settings.apply_args(args);
         settings.check()?;
         info!("settings: {:#?}", settings);
@@ -162,12 +184,36 @@ impl Settings {
         for (name, job) in &config.jobs {
             self.jobs.insert(name.clone(), job.clone());
         }
+        for (name, analyzer) in &config.analyzers {
+            self.analyzers.insert(name.clone(), analyzer.clone());
+        }
         if let Some(default_job) = &config.default_job {
             self.default_job = default_job.clone();
         }
+        if let Some(concurrent_jobs) = &config.concurrent_jobs {
+            self.concurrent_jobs.clone_from(concurrent_jobs);
+        }
+        // dismissals of all configuration files add up
+        for dismissal in config.dismissals.iter().flatten() {
+            if !self.dismissals.contains(dismissal) {
+                self.dismissals.push(dismissal.clone());
+            }
+        }
+        if config.editor.is_some() {
+            self.editor.clone_from(&config.editor);
+        }
         if let Some(listen) = config.listen {
             self.listen = listen;
         }
+        if let Some(b) = config.mouse_capture {
+            self.mouse_capture = b;
+        }
+        if let Some(action) = &config.on_location_click {
+            self.on_location_click = action.clone();
+        }
+        if let Some(b) = config.source_snippets {
+            self.source_snippets = b;
+        }
         self.exports.apply_config(config);
     }
     pub fn apply_args(
@@ -238,6 +284,30 @@ impl Settings {
                 bail!("Invalid configuration : default job ({name:?}) not found in jobs");
             }
         }
+        let jobs = self
+            .jobs
+            .iter()
+            .map(|(name, job)| (name.as_str(), job))
+            .chain(std::iter::once(("all jobs", &self.all_jobs)));
+        for (name, job) in jobs {
+            if let Some(AnalyzerRef::Custom(analyzer)) = &job.analyzer {
+                if !self.analyzers.contains_key(analyzer) {
+                    bail!("Invalid configuration : unknown analyzer {analyzer:?} in job {name:?}");
+                }
+            }
+        }
+        for dismissal in &self.dismissals {
+            if let Err(e) = dismissal.check() {
+                bail!("Invalid configuration : {e}");
+            }
+        }
+        for concrete_job_ref in &self.concurrent_jobs {
+            if let NameOrAlias::Name(name) = &concrete_job_ref.name_or_alias {
+                if !self.jobs.contains_key(name) {
+                    bail!("Invalid configuration : concurrent job ({name:?}) not found in jobs");
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -110,6 +110,18 @@ BaconSkin! {
     change_badge_fg: 235,
     /// Background color of the change badge
     change_badge_bg: 6,
+    /// Foreground color of the badge telling the report is from a previous run
+    stale_badge_fg: 235,
+    /// Background color of the badge telling the report is from a previous run
+    stale_badge_bg: 244,
+    /// Foreground color of the badge counting the items matching the search, when filtering on it
+    search_filter_badge_fg: 235,
+    /// Background color of the badge counting the items matching the search, when filtering on it
+    search_filter_badge_bg: 6,
+    /// Foreground color of the mark of the items which weren't in the previous run
+    new_item_fg: 10,
+    /// Foreground color of the mark of the lines of the selected item
+    selected_item_fg: 14,
     /// Foreground color of the "computing..." indicator
     computing_fg: 235,
     /// Background color of the "computing..." indicator
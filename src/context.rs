@@ -218,6 +218,7 @@ impl Context {
             job,
             paths_to_watch,
             settings,
+            prerequisites: Vec::new(),
         })
     }
     pub fn workspace_cargo_path(&self) -> Option<PathBuf> {
@@ -242,9 +243,25 @@ impl Context {
     pub fn package_dot_config_path(&self) -> PathBuf {
         self.package_directory.join(".config/bacon.toml")
     }
+    /// Where the dismissals done by the user are saved
+    pub fn dismissals_path(&self) -> PathBuf {
+        self.package_directory.join(".bacon-dismissals.json")
+    }
     pub fn unix_socket_path(&self) -> PathBuf {
         self.package_directory.join(".bacon.socket")
     }
+    /// Make a path, as found in a report, absolute, guessing whether it's
+    /// relative to the workspace or to the package
+    pub fn make_absolute(
+        &self,
+        path: PathBuf,
+    ) -> PathBuf {
+        absolute_report_path(
+            path,
+            &self.package_directory,
+            self.workspace_root.as_deref(),
+        )
+    }
 }
 
 fn add_to_paths_to_watch(
@@ -0,0 +1,86 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    lazy_regex::*,
+    std::process::Command,
+};
+
+/// The template used when no `editor` is configured, with `$EDITOR`
+/// as executable. The `+line` syntax is understood by most terminal
+/// editors (vi, vim, neovim, emacs, nano, micro, kakoune, etc.)
+const DEFAULT_EDITOR_ARGS: &[&str] = &["+{line}", "{file}"];
+
+/// The tokens of the command when there's no configured template:
+/// `$EDITOR` may hold arguments (eg `code --wait`), so it's split on
+/// whitespace before the default arguments are appended
+fn default_editor_tokens(editor: &str) -> Vec<String> {
+    editor
+        .split_whitespace()
+        .chain(DEFAULT_EDITOR_ARGS.iter().copied())
+        .map(String::from)
+        .collect()
+}
+
+/// Build the command opening the location in the user's editor.
+///
+/// The template is split on whitespace, then the `{file}`, `{line}`,
+/// and `{column}` placeholders are replaced in every token.
+pub fn editor_command(
+    template: Option<&str>,
+    location: &Location,
+) -> Result<Command> {
+    let tokens: Vec<String> = match template {
+        Some(template) => template.split_whitespace().map(String::from).collect(),
+        None => {
+            let Ok(editor) = std::env::var("EDITOR") else {
+                bail!("No editor configured and $EDITOR isn't set");
+            };
+            default_editor_tokens(&editor)
+        }
+    };
+    let file = location.path.to_string_lossy();
+    let line = location.line.to_string();
+    let column = location.column.unwrap_or(1).to_string();
+    let mut tokens = tokens.iter().map(|token| {
+        regex_replace_all!(r"\{(\w+)\}", token, |whole: &str, key| {
+            match key {
+                "file" => file.to_string(),
+                "line" => line.clone(),
+                "column" => column.clone(),
+                _ => whole.to_string(),
+            }
+        })
+        .to_string()
+    });
+    let Some(exe) = tokens.next() else {
+        bail!("Empty editor command");
+    };
+    let mut command = Command::new(exe);
+    command.args(tokens);
+    Ok(command)
+}
+
+#[test]
+fn test_editor_command() {
+    let location = Location {
+        path: "/home/me/project/src/main.rs".into(),
+        line: 42,
+        column: Some(7),
+    };
+    let command = editor_command(Some("code --goto {file}:{line}:{column}"), &location).unwrap();
+    assert_eq!(command.get_program(), "code");
+    let args: Vec<_> = command.get_args().collect();
+    assert_eq!(args, ["--goto", "/home/me/project/src/main.rs:42:7"]);
+}
+
+#[test]
+fn test_default_editor_tokens() {
+    assert_eq!(default_editor_tokens("vim"), ["vim", "+{line}", "{file}"]);
+    assert_eq!(
+        default_editor_tokens("emacsclient -t"),
+        ["emacsclient", "-t", "+{line}", "{file}"],
+    );
+}
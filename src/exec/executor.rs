@@ -9,6 +9,7 @@ use {
         process::{
             Child,
             Command,
+            ExitStatus,
         },
         thread,
         time::Instant,
@@ -66,6 +67,19 @@ impl TaskExecutor {
             warn!("child_thread.join() failed"); // should not happen
         }
     }
+    /// Send again the end of the task, with its status, when `ready`
+    /// receives a message (or is disconnected)
+    pub fn end_when_ready(
+        &self,
+        ready: Receiver<()>,
+        status: ExitStatus,
+    ) {
+        let line_sender = self._line_sender.clone();
+        thread::spawn(move || {
+            let _ = ready.recv();
+            let _ = line_sender.send(CommandExecInfo::End { status });
+        });
+    }
     pub fn is_in_grace_period(&mut self) -> bool {
         if let Some(grace_period_start) = self.grace_period_start {
             if grace_period_start.elapsed() < self.grace_period.duration {
@@ -1,13 +1,17 @@
 mod command_builder;
+mod editor;
 mod executor;
 mod on_change_strategy;
 mod period;
+mod shell;
 mod task;
 
 pub use {
     command_builder::CommandBuilder,
+    editor::*,
     executor::*,
     on_change_strategy::*,
     period::*,
+    shell::*,
     task::Task,
 };
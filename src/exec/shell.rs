@@ -0,0 +1,173 @@
+use {
+    crate::*,
+    lazy_regex::*,
+    rustc_hash::FxHashMap,
+    std::{
+        io,
+        path::PathBuf,
+        process::{
+            Command,
+            Output,
+        },
+        thread,
+    },
+    termimad::crossbeam::channel::Sender,
+};
+
+/// The result of a command launched by a `shell:` action
+pub struct ShellOutcome {
+    pub command: String,
+    pub output: io::Result<Output>,
+}
+
+impl ShellOutcome {
+    /// Build the message to display, with the exit status and
+    /// the last line written by the command
+    pub fn message(&self) -> Message {
+        let output = match &self.output {
+            Ok(output) => output,
+            Err(e) => {
+                return Message::short(format!("Failed to launch `{}`: {e}", self.command));
+            }
+        };
+        let last_line = |bytes: &[u8]| {
+            String::from_utf8_lossy(bytes)
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| line.to_string())
+        };
+        if output.status.success() {
+            let detail = last_line(&output.stdout).unwrap_or_default();
+            Message::short(format!("`{}` succeeded: {detail}", self.command))
+        } else {
+            let detail = last_line(&output.stderr)
+                .or_else(|| last_line(&output.stdout))
+                .unwrap_or_default();
+            Message::short(format!(
+                "`{}` failed ({}): {detail}",
+                self.command, output.status
+            ))
+        }
+    }
+}
+
+/// Quote a value so that the shell sees it as one argument.
+///
+/// `cmd` has no way to escape a double quote inside a quoted argument,
+/// but double quotes can't be in Windows paths, so they're just removed.
+/// Note that `cmd` still expands `%var%` in quoted arguments.
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', ""))
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Replace the `{name}` placeholders with the quoted values, leaving
+/// the unknown ones untouched
+pub fn fill_shell_placeholders(
+    template: &str,
+    values: &FxHashMap<&str, String>,
+) -> String {
+    regex_replace_all!(r"\{([\w-]+)\}", template, |whole: &str, key| {
+        match values.get(key) {
+            Some(value) => shell_quote(value),
+            None => whole.to_string(),
+        }
+    })
+    .to_string()
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    let mut cmd = Command::new("cmd");
+    // the command line is given as is: the standard escaping of arguments,
+    // with backslashes, isn't understood by cmd
+    cmd.arg("/C").raw_arg(command);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+/// Run the command in the system's shell, in a background thread,
+/// and send the outcome when it's done
+pub fn spawn_shell_command(
+    command: String,
+    dir: PathBuf,
+    tx: Sender<ShellOutcome>,
+) {
+    thread::spawn(move || {
+        info!("running shell command {command:?}");
+        let output = shell_command(&command).current_dir(dir).output();
+        let _ = tx.send(ShellOutcome { command, output });
+    });
+}
+
+impl MissionState<'_, '_> {
+    /// Values of the placeholders of the `shell:` actions, from
+    /// the mission and its top item
+    pub fn shell_placeholders(&self) -> FxHashMap<&'static str, String> {
+        let mut values = FxHashMap::default();
+        values.insert("job", self.mission.concrete_job_ref.badge_label());
+        values.insert(
+            "package-dir",
+            self.mission.package_directory.to_string_lossy().to_string(),
+        );
+        values.insert("changes", self.changes_since_last_job_start.to_string());
+        let report = self.cmd_result.report();
+        let current_item_idx = self.current_item_idx();
+        let item_location = report
+            .zip(current_item_idx)
+            .and_then(|(r, i)| r.item_location(i));
+        let item_diag_type = report
+            .zip(current_item_idx)
+            .and_then(|(r, i)| r.item_diag_type(i));
+        values.insert("location", item_location.unwrap_or_default().to_string());
+        values.insert("diag-type", item_diag_type.unwrap_or_default().to_string());
+        let location = self.current_item_location();
+        values.insert(
+            "file",
+            location
+                .as_ref()
+                .map(|l| l.path.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+        values.insert(
+            "line",
+            location
+                .as_ref()
+                .map(|l| l.line.to_string())
+                .unwrap_or_default(),
+        );
+        values.insert(
+            "column",
+            location
+                .as_ref()
+                .and_then(|l| l.column)
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+        );
+        values
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fill_shell_placeholders() {
+    let mut values = FxHashMap::default();
+    values.insert("file", "/home/me/it's/main.rs".to_string());
+    values.insert("job", "clippy".to_string());
+    assert_eq!(
+        fill_shell_placeholders("git add {file} && echo {job} {unknown}", &values),
+        r"git add '/home/me/it'\''s/main.rs' && echo 'clippy' {unknown}",
+    );
+}
@@ -20,4 +20,7 @@ pub struct ExportConfig {
 
     /// Optional format string used by exporters that write line-based data.
     pub line_format: Option<String>,
+
+    /// Path to the template file used by the `template` exporter.
+    pub template: Option<PathBuf>,
 }
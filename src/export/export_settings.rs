@@ -1,7 +1,14 @@
 use {
     crate::*,
+    anyhow::{
+        Context,
+        bail,
+    },
     std::{
-        fs::File,
+        fs::{
+            self,
+            File,
+        },
         path::PathBuf,
     },
 };
@@ -13,11 +20,16 @@ pub struct ExportSettings {
     pub auto: bool,
     pub path: PathBuf,
     pub line_format: String,
+    /// The template file, for the `template` exporter
+    pub template: Option<PathBuf>,
 }
 impl ExportSettings {
+    /// Export the report, which may be the whole report of the mission or,
+    /// when the user requested the export, the one reduced by the search filter
     pub fn do_export(
         &self,
         name: &str,
+        report: &Report,
         state: &MissionState<'_, '_>,
     ) -> anyhow::Result<()> {
         let path = if self.path.is_relative() {
@@ -26,10 +38,6 @@ impl ExportSettings {
             self.path.clone()
         };
         info!("exporting to {path:?}");
-        let Some(report) = state.cmd_result.report() else {
-            info!("No report to export");
-            return Ok(());
-        };
         match self.exporter {
             Exporter::Analyser => {
                 if let Some(export) = report.analyzer_exports.get(name) {
@@ -45,10 +53,31 @@ impl ExportSettings {
                 let json = serde_json::to_string_pretty(&report)?;
                 std::fs::write(&path, json)?;
             }
+            Exporter::Junit => {
+                let job_name = state.mission.concrete_job_ref.badge_label();
+                std::fs::write(&path, junit_xml(report, &job_name))?;
+            }
             Exporter::Locations => {
                 let mut file = File::create(path)?;
                 report.write_locations(&mut file, &state.mission, &self.line_format)?;
             }
+            Exporter::Template => {
+                let Some(template_path) = &self.template else {
+                    bail!("No template defined for export {name:?}");
+                };
+                let template_path = state.mission.package_directory.join(template_path);
+                let template = fs::read_to_string(&template_path)
+                    .with_context(|| format!("Can't read template {}", template_path.display()))?;
+                let template = ReportTemplate::new(&template)
+                    .with_context(|| format!("Invalid template {}", template_path.display()))?;
+                let data = ReportTemplateData::new(report, &state.mission);
+                fs::write(&path, template.render(&data)?)?;
+            }
+            Exporter::Sarif => {
+                let log = SarifLog::of_report(report, &state.mission);
+                let json = serde_json::to_string_pretty(&log)?;
+                std::fs::write(&path, json)?;
+            }
         }
         Ok(())
     }
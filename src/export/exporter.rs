@@ -16,6 +16,12 @@ pub enum Exporter {
     Analysis,
     /// Emit a machine-readable JSON report for the mission.
     JsonReport,
+    /// Write a JUnit XML report of the tests, for dashboards.
+    Junit,
     /// Produce a list of file locations for editors or other tools.
     Locations,
+    /// Write a SARIF 2.1.0 log, for code review tools and IDE viewers.
+    Sarif,
+    /// Render the report with a template file.
+    Template,
 }
@@ -24,27 +24,39 @@ impl ExportsSettings {
         locations_export.auto = enabled;
     }
 
+    /// Do the automatic exports, always of the whole report, even
+    /// when the search filter is on
     pub fn do_auto_exports(
         &self,
         state: &MissionState<'_, '_>,
     ) {
+        let Some(report) = state.cmd_result.report() else {
+            info!("No report to export");
+            return;
+        };
         for (name, export) in &self.exports {
             if export.auto {
                 info!("doing auto export {name:?}");
-                if let Err(e) = export.do_export(name, state) {
+                if let Err(e) = export.do_export(name, report, state) {
                     error!("error while exporting {name:?}: {e:?}");
                 }
             }
         }
     }
 
+    /// Do the export requested by the user, of the items matching
+    /// the search when the search filter is on
     pub fn do_named_export(
         &self,
         requested_name: &str,
         state: &MissionState<'_, '_>,
     ) {
+        let Some(report) = state.report_to_export() else {
+            info!("No report to export");
+            return;
+        };
         if let Some(export) = self.exports.get(requested_name) {
-            if let Err(e) = export.do_export(requested_name, state) {
+            if let Err(e) = export.do_export(requested_name, &report, state) {
                 error!("error while exporting {requested_name:?}: {e:?}");
             }
         } else {
@@ -80,14 +92,20 @@ impl ExportsSettings {
                 if let Some(lf) = &ec.line_format {
                     e.line_format.clone_from(lf);
                 }
+                if let Some(t) = &ec.template {
+                    e.template = Some(t.clone());
+                }
                 continue;
             }
             let exporter = match ec.exporter {
                 Some(e) => e,
+                None if ec.template.is_some() => Exporter::Template,
                 None => match name.as_str() {
                     "analysis" => Exporter::Analysis,
                     "json-report" => Exporter::JsonReport,
+                    "junit" => Exporter::Junit,
                     "locations" => Exporter::Locations,
+                    "sarif" => Exporter::Sarif,
                     _ => {
                         warn!("Exporter not specified for export {name:?}, using 'locations'");
                         Exporter::Locations
@@ -100,6 +118,9 @@ impl ExportsSettings {
                 Exporter::Analysis => default_analysis_path(),
                 Exporter::Locations => default_locations_path(),
                 Exporter::JsonReport => default_json_report_path(),
+                Exporter::Junit => default_junit_path(),
+                Exporter::Sarif => default_sarif_path(),
+                Exporter::Template => default_template_export_path(),
             });
             let line_format = ec.line_format.clone().unwrap_or_else(|| match exporter {
                 Exporter::Locations => default_locations_line_format().to_string(),
@@ -112,6 +133,7 @@ impl ExportsSettings {
                     auto,
                     path,
                     line_format,
+                    template: ec.template.clone(),
                 },
             );
         }
@@ -179,6 +201,7 @@ fn default_analysis_export_settings() -> ExportSettings {
         auto: true,
         path: default_analysis_path(),
         line_format: String::new(), // not used
+        template: None,
     }
 }
 fn default_json_report_export_settings() -> ExportSettings {
@@ -187,6 +210,7 @@ fn default_json_report_export_settings() -> ExportSettings {
         auto: true,
         path: default_json_report_path(),
         line_format: String::new(), // not used
+        template: None,
     }
 }
 fn default_locations_export_settings() -> ExportSettings {
@@ -195,6 +219,7 @@ fn default_locations_export_settings() -> ExportSettings {
         auto: true,
         path: default_locations_path(),
         line_format: default_locations_line_format().to_string(),
+        template: None,
     }
 }
 
@@ -211,6 +236,15 @@ pub fn default_analysis_path() -> PathBuf {
 pub fn default_json_report_path() -> PathBuf {
     PathBuf::from("bacon-report.json")
 }
+pub fn default_junit_path() -> PathBuf {
+    PathBuf::from("bacon-junit.xml")
+}
 pub fn default_locations_path() -> PathBuf {
     PathBuf::from(".bacon-locations")
 }
+pub fn default_sarif_path() -> PathBuf {
+    PathBuf::from("bacon.sarif")
+}
+pub fn default_template_export_path() -> PathBuf {
+    PathBuf::from("bacon-export.txt")
+}
@@ -0,0 +1,207 @@
+use {
+    crate::*,
+    std::fmt::Write as _,
+};
+
+/// A test case of a JUnit report
+struct JunitTestCase {
+    name: String,
+    classname: Option<String>,
+    outcome: JunitOutcome,
+}
+
+enum JunitOutcome {
+    Passed,
+    /// A failed test, with its output
+    Failure(String),
+    /// An error preventing tests to run (eg a compilation error),
+    /// with its title and its other lines
+    Error {
+        message: String,
+        output: String,
+    },
+}
+
+/// Build the JUnit XML report of the tests of a report: the failed
+/// tests with their output, the passed ones when the analyzer
+/// recognized them, and the errors which prevented tests to run
+pub fn junit_xml(
+    report: &Report,
+    suite_name: &str,
+) -> String {
+    let mut test_cases: Vec<JunitTestCase> = report
+        .passed_tests
+        .iter()
+        .map(|name| JunitTestCase {
+            name: name.clone(),
+            classname: None,
+            outcome: JunitOutcome::Passed,
+        })
+        .collect();
+    test_cases.sort_by(|a, b| a.name.cmp(&b.name));
+    for item in Item::items_of(&report.lines) {
+        let Some((title, output)) = item.lines().split_first() else {
+            continue;
+        };
+        let is_error = match title.line_type {
+            LineType::Title(Kind::TestFail | Kind::TestOutput) => false,
+            LineType::Title(Kind::Error) => true,
+            _ => continue,
+        };
+        let title = title.content.to_raw();
+        let output = output
+            .iter()
+            .map(|line| line.content.to_raw())
+            .collect::<Vec<String>>()
+            .join("\n")
+            .trim_end()
+            .to_string();
+        if is_error {
+            let name = match item.location() {
+                Some(location) => format!("{title} ({location})"),
+                None => title.clone(),
+            };
+            test_cases.push(JunitTestCase {
+                name,
+                classname: Some(suite_name.to_string()),
+                outcome: JunitOutcome::Error {
+                    message: title,
+                    output,
+                },
+            });
+            continue;
+        }
+        let Some((status, name)) = title.split_once(": ") else {
+            continue;
+        };
+        if status == "passed" {
+            continue; // output of a passed test, already listed
+        }
+        test_cases.push(JunitTestCase {
+            name: name.trim().to_string(),
+            classname: None,
+            outcome: JunitOutcome::Failure(output),
+        });
+    }
+    let failures = test_cases
+        .iter()
+        .filter(|test_case| matches!(test_case.outcome, JunitOutcome::Failure(_)))
+        .count();
+    let errors = test_cases
+        .iter()
+        .filter(|test_case| matches!(test_case.outcome, JunitOutcome::Error { .. }))
+        .count();
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<testsuites>\n");
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+        xml_escape(suite_name),
+        test_cases.len(),
+        failures,
+        errors,
+    );
+    for test_case in &test_cases {
+        let classname = test_case.classname.as_deref().unwrap_or_else(|| {
+            test_case
+                .name
+                .rsplit_once("::")
+                .map_or(suite_name, |(module, _)| module)
+        });
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\"",
+            xml_escape(&test_case.name),
+            xml_escape(classname),
+        );
+        match &test_case.outcome {
+            JunitOutcome::Passed => {
+                xml.push_str("/>\n");
+            }
+            JunitOutcome::Failure(output) => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"test failed\">{}</failure>\n    </testcase>",
+                    xml_escape(output),
+                );
+            }
+            JunitOutcome::Error { message, output } => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <error message=\"{}\">{}</error>\n    </testcase>",
+                    xml_escape(message),
+                    xml_escape(output),
+                );
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // characters not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[test]
+fn test_junit_xml() {
+    let mut report = Report::new(vec![
+        Line {
+            item_idx: 1,
+            line_type: LineType::Title(Kind::TestFail),
+            content: TLine::failed("tests::fails"),
+        },
+        Line {
+            item_idx: 1,
+            line_type: LineType::Normal,
+            content: TLine::from_raw("assertion `left == right` failed: 1 < 2".to_string()),
+        },
+    ]);
+    report.passed_tests = vec!["tests::works".to_string()];
+    let xml = junit_xml(&report, "test");
+    assert!(xml.contains(r#"<testsuite name="test" tests="2" failures="1" errors="0">"#));
+    assert!(xml.contains(r#"<testcase name="tests::works" classname="tests"/>"#));
+    assert!(xml.contains(r#"<testcase name="tests::fails" classname="tests">"#));
+    assert!(xml.contains("failed: 1 &lt; 2</failure>"));
+}
+
+#[test]
+fn test_junit_xml_compile_error() {
+    let report = Report::new(vec![
+        Line {
+            item_idx: 1,
+            line_type: LineType::Title(Kind::Error),
+            content: TLine::from_raw(
+                "error[E0425]: cannot find value `y` in this scope".to_string(),
+            ),
+        },
+        Line {
+            item_idx: 1,
+            line_type: LineType::Location,
+            content: TLine::from_raw("  --> src/lib.rs:4:13".to_string()),
+        },
+    ]);
+    let xml = junit_xml(&report, "test");
+    assert!(xml.contains(r#"<testsuite name="test" tests="1" failures="0" errors="1">"#));
+    assert!(xml.contains(
+        r#"<testcase name="error[E0425]: cannot find value `y` in this scope (src/lib.rs:4:13)" classname="test">"#
+    ));
+    assert!(xml.contains(
+        r#"<error message="error[E0425]: cannot find value `y` in this scope">  --&gt; src/lib.rs:4:13</error>"#
+    ));
+}
@@ -2,10 +2,16 @@ mod export_config;
 mod export_settings;
 mod exporter;
 mod exports_settings;
+mod junit;
+mod report_template;
+mod sarif;
 
 pub use {
     export_config::*,
     export_settings::*,
     exporter::*,
     exports_settings::*,
+    junit::*,
+    report_template::*,
+    sarif::*,
 };
@@ -0,0 +1,402 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    iq::IQ,
+    lazy_regex::*,
+    serde::{
+        Serialize,
+        Serializer,
+        ser::SerializeMap,
+    },
+    serde_json::Value,
+};
+
+/// A template rendering a whole report.
+///
+/// Values are inserted with `{path}` (eg `{stats.warnings}`), or with
+/// `{path|json}` to get them as JSON. Arrays are iterated with
+/// `{#each items as item}...{/each}`, an optional `{#sep}` in the
+/// loop introducing the text to write between iterations.
+///
+/// Paths are resolved by iq, so they can go deep in the data (eg
+/// `{item.lines.0}`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportTemplate {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(String),
+    Value {
+        path: String,
+        json: bool,
+    },
+    Each {
+        path: String,
+        var: String,
+        body: Vec<Node>,
+        separator: Vec<Node>,
+    },
+}
+
+/// What can be found between braces in a template
+enum Tag {
+    Value { path: String, json: bool },
+    Each { path: String, var: String },
+    Sep,
+    EndEach,
+}
+
+impl Tag {
+    fn parse(content: &str) -> Option<Self> {
+        if content == "#sep" {
+            return Some(Self::Sep);
+        }
+        if content == "/each" {
+            return Some(Self::EndEach);
+        }
+        if let Some((_, path, var)) = regex_captures!(r"^#each\s+([\w.-]+)\s+as\s+(\w+)$", content)
+        {
+            return Some(Self::Each {
+                path: path.to_string(),
+                var: var.to_string(),
+            });
+        }
+        let (_, path, filter) = regex_captures!(r"^([\w.-]+)(\|json)?$", content)?;
+        Some(Self::Value {
+            path: path.to_string(),
+            json: !filter.is_empty(),
+        })
+    }
+}
+
+/// A loop being parsed
+struct Frame {
+    path: String,
+    var: String,
+    body: Vec<Node>,
+    separator: Option<Vec<Node>>,
+}
+
+/// The data given to a report template
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTemplateData {
+    pub job: TemplateJob,
+    pub success: bool,
+    pub error_code: Option<i32>,
+    pub stats: Stats,
+    pub items: Vec<TemplateItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateJob {
+    pub name: String,
+    pub command: Vec<String>,
+    pub package_directory: String,
+}
+
+/// An error, warning, or test failure, with the lines of the report
+/// which describe it
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateItem {
+    pub idx: usize,
+    /// "error", "warning", or "test"
+    pub kind: &'static str,
+    pub diag_type: Option<String>,
+    pub code: Option<String>,
+    pub message: String,
+    /// the path, as given by the tool
+    pub path: Option<String>,
+    pub absolute_path: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub fingerprint: String,
+    /// the lines of the tool output, without the source snippets added by bacon
+    pub lines: Vec<String>,
+}
+
+/// The data available while rendering: the root data and the
+/// variables of the enclosing loops, the innermost ones first
+struct Scope<'s> {
+    root: &'s Value,
+    vars: Vec<(&'s str, &'s Value)>,
+}
+
+impl Serialize for Scope<'_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let mut names = Vec::new();
+        for (name, value) in &self.vars {
+            if !names.contains(name) {
+                map.serialize_entry(name, value)?;
+                names.push(name);
+            }
+        }
+        if let Value::Object(root) = self.root {
+            for (name, value) in root {
+                if !names.contains(&name.as_str()) {
+                    map.serialize_entry(name, value)?;
+                }
+            }
+        }
+        map.end()
+    }
+}
+
+impl ReportTemplate {
+    pub fn new(template: &str) -> Result<Self> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut last_end = 0;
+        for captures in regex!(r"\{([^{}]+)\}").captures_iter(template) {
+            let Some(tag) = Tag::parse(&captures[1]) else {
+                continue; // not a tag, eg some JSON
+            };
+            let tag_match = captures.get(0).unwrap();
+            if tag_match.start() > last_end {
+                let literal = Node::Literal(template[last_end..tag_match.start()].to_string());
+                current_nodes(&mut stack, &mut nodes).push(literal);
+            }
+            last_end = tag_match.end();
+            match tag {
+                Tag::Value { path, json } => {
+                    current_nodes(&mut stack, &mut nodes).push(Node::Value { path, json });
+                }
+                Tag::Each { path, var } => {
+                    stack.push(Frame {
+                        path,
+                        var,
+                        body: Vec::new(),
+                        separator: None,
+                    });
+                }
+                Tag::Sep => {
+                    let Some(frame) = stack.last_mut() else {
+                        bail!("{{#sep}} outside of a {{#each}} loop");
+                    };
+                    if frame.separator.is_some() {
+                        bail!("several {{#sep}} in a {{#each}} loop");
+                    }
+                    frame.separator = Some(Vec::new());
+                }
+                Tag::EndEach => {
+                    let Some(frame) = stack.pop() else {
+                        bail!("{{/each}} without {{#each}}");
+                    };
+                    let each = Node::Each {
+                        path: frame.path,
+                        var: frame.var,
+                        body: frame.body,
+                        separator: frame.separator.unwrap_or_default(),
+                    };
+                    current_nodes(&mut stack, &mut nodes).push(each);
+                }
+            }
+        }
+        if let Some(frame) = stack.last() {
+            bail!("{{#each {} as {}}} isn't closed", frame.path, frame.var);
+        }
+        if last_end < template.len() {
+            nodes.push(Node::Literal(template[last_end..].to_string()));
+        }
+        Ok(Self { nodes })
+    }
+    pub fn render<T: Serialize>(
+        &self,
+        data: &T,
+    ) -> Result<String> {
+        let root = serde_json::to_value(data)?;
+        let scope = Scope {
+            root: &root,
+            vars: Vec::new(),
+        };
+        let mut rendered = String::new();
+        render_nodes(&self.nodes, &scope, &mut rendered);
+        Ok(rendered)
+    }
+}
+
+fn current_nodes<'a>(
+    stack: &'a mut [Frame],
+    nodes: &'a mut Vec<Node>,
+) -> &'a mut Vec<Node> {
+    match stack.last_mut() {
+        Some(Frame {
+            separator: Some(separator),
+            ..
+        }) => separator,
+        Some(frame) => &mut frame.body,
+        None => nodes,
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    scope: &Scope<'_>,
+    rendered: &mut String,
+) {
+    for node in nodes {
+        match node {
+            Node::Literal(literal) => {
+                rendered.push_str(literal);
+            }
+            Node::Value { path, json: false } => {
+                if let Some(value) = scope.extract_primitive(path.as_str()) {
+                    rendered.push_str(&value);
+                }
+            }
+            Node::Value { path, json: true } => {
+                let json = scope.extract_json(path.as_str());
+                rendered.push_str(json.as_deref().unwrap_or("null"));
+            }
+            Node::Each {
+                path,
+                var,
+                body,
+                separator,
+            } => {
+                let elements: Vec<Value> = match scope.extract_value(path.as_str()) {
+                    Ok(Some(elements)) => elements,
+                    Ok(None) => Vec::new(),
+                    Err(e) => {
+                        warn!("can't iterate over {path:?}: {e}");
+                        Vec::new()
+                    }
+                };
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        render_nodes(separator, scope, rendered);
+                    }
+                    let mut vars = vec![(var.as_str(), element)];
+                    vars.extend(scope.vars.iter().copied());
+                    let element_scope = Scope {
+                        root: scope.root,
+                        vars,
+                    };
+                    render_nodes(body, &element_scope, rendered);
+                }
+            }
+        }
+    }
+}
+
+impl ReportTemplateData {
+    pub fn new(
+        report: &Report,
+        mission: &Mission,
+    ) -> Self {
+        let fingerprints = report.item_fingerprints();
+        let items = Item::items_of(&report.lines)
+            .into_iter()
+            .filter_map(|item| {
+                let title = item
+                    .lines()
+                    .iter()
+                    .find(|line| matches!(line.line_type, LineType::Title(_)))?;
+                let kind = match title.line_type {
+                    LineType::Title(Kind::Error) => "error",
+                    LineType::Title(Kind::Warning) => "warning",
+                    LineType::Title(Kind::TestFail | Kind::TestOutput) => "test",
+                    _ => {
+                        return None;
+                    }
+                };
+                let idx = item.item_idx();
+                let location = item
+                    .location()
+                    .and_then(|location| location.parse::<Location>().ok());
+                Some(TemplateItem {
+                    idx,
+                    kind,
+                    diag_type: item.diag_type().map(|s| s.to_string()),
+                    code: report.item_code(idx),
+                    message: title.title_message().unwrap_or_default().to_string(),
+                    path: location
+                        .as_ref()
+                        .map(|location| location.path.to_string_lossy().to_string()),
+                    absolute_path: location.as_ref().map(|location| {
+                        mission
+                            .make_absolute(location.path.clone())
+                            .to_string_lossy()
+                            .to_string()
+                    }),
+                    line: location.as_ref().map(|location| location.line),
+                    column: location.as_ref().and_then(|location| location.column),
+                    fingerprint: fingerprints
+                        .iter()
+                        .find(|(item_idx, _)| *item_idx == idx)
+                        .map(|(_, fingerprint)| fingerprint.clone())
+                        .unwrap_or_default(),
+                    lines: item
+                        .lines()
+                        .iter()
+                        .filter(|line| line.line_type != LineType::Snippet)
+                        .map(|line| line.content.to_raw())
+                        .collect(),
+                })
+            })
+            .collect();
+        Self {
+            job: TemplateJob {
+                name: mission.concrete_job_ref.badge_label(),
+                command: mission.job.command.clone(),
+                package_directory: mission.package_directory.to_string_lossy().to_string(),
+            },
+            success: mission.is_success(report),
+            error_code: report.error_code(),
+            stats: report.stats.clone(),
+            items,
+        }
+    }
+}
+
+#[test]
+fn test_report_template() {
+    #[derive(Serialize)]
+    struct Item {
+        name: &'static str,
+        tags: Vec<&'static str>,
+    }
+    #[derive(Serialize)]
+    struct Data {
+        title: &'static str,
+        items: Vec<Item>,
+    }
+    let data = Data {
+        title: "Report",
+        items: vec![
+            Item {
+                name: "a \"b\"",
+                tags: vec!["x", "y"],
+            },
+            Item {
+                name: "c",
+                tags: vec![],
+            },
+        ],
+    };
+    let template = ReportTemplate::new(
+        "# {title}\n{#each items as item}- {item.name} ({#each item.tags as tag}{tag}{#sep}, {/each}) in {title}\n{/each}",
+    )
+    .unwrap();
+    assert_eq!(
+        template.render(&data).unwrap(),
+        "# Report\n- a \"b\" (x, y) in Report\n- c () in Report\n",
+    );
+    let template =
+        ReportTemplate::new(r#"[{#each items as item}{"name":{item.name|json}}{#sep},{/each}]"#)
+            .unwrap();
+    assert_eq!(
+        template.render(&data).unwrap(),
+        r#"[{"name":"a \"b\""},{"name":"c"}]"#,
+    );
+    assert!(ReportTemplate::new("{#each items as item}").is_err());
+    assert!(ReportTemplate::new("{/each}").is_err());
+}
@@ -0,0 +1,330 @@
+use {
+    crate::*,
+    serde::Serialize,
+    std::{
+        collections::BTreeMap,
+        path::{
+            Path,
+            PathBuf,
+        },
+    },
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The base of the relative URIs, which is the workspace directory
+/// (or the package directory when there's no workspace)
+const SRCROOT: &str = "%SRCROOT%";
+
+/// A SARIF 2.1.0 log, as consumed by code review tools and IDE viewers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub original_uri_base_ids: BTreeMap<&'static str, SarifArtifactLocation>,
+    /// How columns are counted: our columns are in chars, while SARIF
+    /// defaults to UTF-16 code units
+    pub column_kind: &'static str,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SarifRule {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// "error" or "warning"
+    pub level: &'static str,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+/// The location of a file, either as an absolute `file://` URI,
+/// or relative to a base (eg `%SRCROOT%`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<&'static str>,
+}
+
+/// A region of a file, one based
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+}
+
+impl SarifLog {
+    /// Build the SARIF log of the errors, warnings and test failures of a report
+    pub fn of_report(
+        report: &Report,
+        mission: &Mission,
+    ) -> Self {
+        let root = mission
+            .workspace_directory
+            .as_ref()
+            .unwrap_or(&mission.package_directory);
+        Self::of_report_with(report, root, |path| mission.make_absolute(path))
+    }
+    fn of_report_with<F: Fn(PathBuf) -> PathBuf>(
+        report: &Report,
+        root: &Path,
+        make_absolute: F,
+    ) -> Self {
+        let root_uri = format!("{}/", path_to_uri(root).trim_end_matches('/'));
+        // paths in the root are given relative to it, as preferred by most
+        // tools, the other ones are given as absolute URIs
+        let artifact_location = |path: PathBuf| {
+            let uri = path_to_uri(&make_absolute(path));
+            match uri.strip_prefix(&root_uri) {
+                Some(relative) => SarifArtifactLocation {
+                    uri: relative.to_string(),
+                    uri_base_id: Some(SRCROOT),
+                },
+                None => SarifArtifactLocation {
+                    uri,
+                    uri_base_id: None,
+                },
+            }
+        };
+        let results: Vec<SarifResult> = Item::items_of(&report.lines)
+            .into_iter()
+            .filter_map(|item| SarifResult::of_item(item, report, artifact_location))
+            .collect();
+        let mut rules: Vec<SarifRule> = results
+            .iter()
+            .filter_map(|result| result.rule_id.clone())
+            .map(|id| SarifRule { id })
+            .collect();
+        rules.sort_by(|a, b| a.id.cmp(&b.id));
+        rules.dedup();
+        Self {
+            schema: SARIF_SCHEMA,
+            version: "2.1.0",
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "bacon",
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: "https://dystroy.org/bacon",
+                        rules,
+                    },
+                },
+                original_uri_base_ids: BTreeMap::from([(
+                    SRCROOT,
+                    SarifArtifactLocation {
+                        uri: root_uri,
+                        uri_base_id: None,
+                    },
+                )]),
+                column_kind: "unicodeCodePoints",
+                results,
+            }],
+        }
+    }
+}
+
+impl SarifResult {
+    /// Build the result of a report item, if it's an error, a warning,
+    /// or a test failure
+    fn of_item<F: Fn(PathBuf) -> SarifArtifactLocation>(
+        item: Item<'_>,
+        report: &Report,
+        artifact_location: F,
+    ) -> Option<Self> {
+        let title = item
+            .lines()
+            .iter()
+            .find(|line| matches!(line.line_type, LineType::Title(_)))?;
+        let level = match title.line_type {
+            LineType::Title(Kind::Error | Kind::TestFail) => "error",
+            LineType::Title(Kind::Warning) => "warning",
+            _ => {
+                return None;
+            }
+        };
+        let rule_id = item
+            .diag_type()
+            .map(|diag_type| diag_type.to_string())
+            .or_else(|| report.item_code(item.item_idx()));
+        let text = item
+            .lines()
+            .iter()
+            .filter(|line| line.line_type != LineType::Snippet)
+            .map(|line| line.content.to_raw())
+            .collect::<Vec<String>>()
+            .join("\n")
+            .trim_end()
+            .to_string();
+        let primary_span = report
+            .item_diagnostics
+            .get(&item.item_idx())
+            .and_then(|diagnostic| diagnostic.spans.iter().find(|span| span.is_primary));
+        let location = if let Some(span) = primary_span {
+            Some((
+                PathBuf::from(&span.file_name),
+                SarifRegion {
+                    start_line: span.line_start,
+                    start_column: Some(span.column_start),
+                    end_line: Some(span.line_end),
+                    end_column: Some(span.column_end),
+                },
+            ))
+        } else {
+            item.location()
+                .and_then(|location| location.parse::<Location>().ok())
+                .map(|location| {
+                    (
+                        location.path,
+                        SarifRegion {
+                            start_line: location.line,
+                            start_column: location.column,
+                            end_line: None,
+                            end_column: None,
+                        },
+                    )
+                })
+        };
+        let locations = location
+            .map(|(path, region)| SarifLocation {
+                physical_location: SarifPhysicalLocation {
+                    artifact_location: artifact_location(path),
+                    region,
+                },
+            })
+            .into_iter()
+            .collect();
+        Some(Self {
+            rule_id,
+            level,
+            message: SarifMessage { text },
+            locations,
+        })
+    }
+}
+
+#[test]
+fn test_sarif_log() {
+    let lines = vec![
+        Line {
+            item_idx: 1,
+            line_type: LineType::Title(Kind::Warning),
+            content: TLine {
+                strings: vec![
+                    TString::new("\u{1b}[1m\u{1b}[33m", "warning"),
+                    TString::new("", ": unused variable: `x`"),
+                ],
+            },
+        },
+        Line {
+            item_idx: 1,
+            line_type: LineType::Location,
+            content: TLine::from_raw("  --> src/main.rs:3:9".to_string()),
+        },
+        Line {
+            item_idx: 2,
+            line_type: LineType::Title(Kind::Warning),
+            content: TLine::from_raw("warning: unused import".to_string()),
+        },
+        Line {
+            item_idx: 2,
+            line_type: LineType::Location,
+            content: TLine::from_raw("  --> /elsewhere/lib.rs:1:5".to_string()),
+        },
+        Line {
+            item_idx: 3,
+            line_type: LineType::Title(Kind::Sum),
+            content: TLine::from_raw("warning: 1 warning emitted".to_string()),
+        },
+    ];
+    let report = Report::new(lines);
+    let log = SarifLog::of_report_with(&report, Path::new("/ws"), |path| {
+        PathBuf::from("/ws").join(path)
+    });
+    let json = serde_json::to_value(&log).unwrap();
+    let result = &json["runs"][0]["results"][0];
+    assert_eq!(json["version"], "2.1.0");
+    assert_eq!(json["runs"][0]["results"].as_array().unwrap().len(), 2);
+    assert_eq!(result["level"], "warning");
+    assert_eq!(
+        result["message"]["text"],
+        "warning: unused variable: `x`\n  --> src/main.rs:3:9"
+    );
+    let physical_location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(physical_location["artifactLocation"]["uri"], "src/main.rs");
+    assert_eq!(
+        physical_location["artifactLocation"]["uriBaseId"],
+        "%SRCROOT%"
+    );
+    assert_eq!(
+        json["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+        "file:///ws/"
+    );
+    assert_eq!(json["runs"][0]["columnKind"], "unicodeCodePoints");
+    let other_location = &json["runs"][0]["results"][1]["locations"][0]["physicalLocation"];
+    assert_eq!(
+        other_location["artifactLocation"]["uri"],
+        "file:///elsewhere/lib.rs"
+    );
+    assert!(other_location["artifactLocation"]["uriBaseId"].is_null());
+    assert_eq!(physical_location["region"]["startLine"], 3);
+    assert_eq!(physical_location["region"]["startColumn"], 9);
+    assert_eq!(result["ruleId"], "unused variable");
+    assert_eq!(
+        json["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+        "unused variable"
+    );
+}
@@ -0,0 +1,115 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    lazy_regex::*,
+    std::{
+        path::Path,
+        process::Command,
+    },
+    termimad::{
+        Area,
+        FmtText,
+        MadSkin,
+        TextView,
+    },
+};
+
+/// A page displaying the explanation of an error code or lint,
+/// as given by `rustc --explain` or `cargo clippy --explain`
+pub struct ExplainPage {
+    area: Area,
+    skin: MadSkin,
+    markdown: String,
+    scroll: usize,
+}
+
+impl ExplainPage {
+    /// Build the page by running the tool explaining the code
+    /// (eg `E0502` or `clippy::needless_return`)
+    pub fn new(
+        code: &str,
+        dir: &Path,
+        settings: &Settings,
+    ) -> Result<Self> {
+        let mut command = explain_command(code)?;
+        command.current_dir(dir);
+        info!("explaining {code:?} with {command:?}");
+        let output = command.output()?;
+        let explanation = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || explanation.trim().is_empty() {
+            bail!("No explanation found for {code}");
+        }
+        let mut skin = MadSkin::default();
+        skin.bullet.set_fg(settings.all_jobs.skin.key_fg.color());
+        Ok(Self {
+            area: Area::default(),
+            skin,
+            markdown: format!("# {code}\n\n{explanation}"),
+            scroll: 0,
+        })
+    }
+
+    /// draw the explanation in the given area
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        area: Area,
+    ) -> Result<()> {
+        self.area = area;
+        let fmt_text = FmtText::from(
+            &self.skin,
+            &self.markdown,
+            Some((self.area.width - 1) as usize),
+        );
+        let mut text_view = TextView::from(&self.area, &fmt_text);
+        self.scroll = text_view.set_scroll(self.scroll);
+        Ok(text_view.write_on(w)?)
+    }
+
+    pub fn apply_scroll_command(
+        &mut self,
+        cmd: ScrollCommand,
+    ) {
+        let fmt_text = FmtText::from(
+            &self.skin,
+            &self.markdown,
+            Some((self.area.width - 1) as usize),
+        );
+        let mut text_view = TextView::from(&self.area, &fmt_text);
+        text_view.set_scroll(self.scroll);
+        match cmd {
+            ScrollCommand::Top => {
+                text_view.scroll = 0;
+            }
+            ScrollCommand::Bottom => {
+                text_view.set_scroll(text_view.content_height());
+            }
+            ScrollCommand::Lines(lines) => {
+                text_view.try_scroll_lines(lines);
+            }
+            ScrollCommand::MilliPages(milli_pages) => {
+                text_view.try_scroll_pages(f64::from(milli_pages) / 1000f64);
+            }
+        }
+        self.scroll = text_view.scroll;
+    }
+}
+
+/// Build the command explaining the code: rustc for error codes,
+/// clippy for its lints
+fn explain_command(code: &str) -> Result<Command> {
+    if regex_is_match!(r"^E\d{4}$", code) {
+        let mut command = Command::new("rustc");
+        command.arg("--explain").arg(code);
+        Ok(command)
+    } else if let Some(lint) = code.strip_prefix("clippy::") {
+        let mut command = Command::new("cargo");
+        command.arg("clippy").arg("--explain").arg(lint);
+        Ok(command)
+    } else {
+        bail!("No explanation available for {code}");
+    }
+}
@@ -4,13 +4,17 @@ pub struct HelpLine {
     clear_search: Option<String>,
     close_help: Option<String>,
     help: Option<String>,
+    new_only: Option<String>,
     next_match: Option<String>,
     not_wrap: Option<String>,
+    not_new_only: Option<String>,
     pause: Option<String>,
     previous_match: Option<String>,
     quit: String,
     scope: Option<String>,
     search: Option<String>,
+    search_filter: Option<String>,
+    not_search_filter: Option<String>,
     toggle_backtrace: Option<String>,
     toggle_summary: Option<String>,
     undismiss: Option<String>,
@@ -50,6 +54,12 @@ impl HelpLine {
             .shortest_key_for(&Action::Back)
             .or_else(|| kb.shortest_key_for(&Action::Help))
             .map(|k| format!("*{k}* to close this help"));
+        let new_only = kb
+            .shortest_key_for(&Action::ToggleNewOnly)
+            .map(|k| format!("*{k}* to show only new items"));
+        let not_new_only = kb
+            .shortest_key_for(&Action::ToggleNewOnly)
+            .map(|k| format!("*{k}* to show all items"));
         let pause = kb
             .shortest_key_for(&Action::Pause)
             .or(kb.shortest_key_for(&Action::TogglePause))
@@ -73,6 +83,12 @@ impl HelpLine {
         let clear_search = kb
             .shortest_key_for(&Action::Back)
             .map(|k| format!("*{k}* to clear"));
+        let search_filter = kb
+            .shortest_key_for(&Action::ToggleSearchFilter)
+            .map(|k| format!("*{k}* to show only matching items"));
+        let not_search_filter = kb
+            .shortest_key_for(&Action::ToggleSearchFilter)
+            .map(|k| format!("*{k}* to show all items"));
         let validate_search = kb
             .shortest_key_for(&Action::Validate)
             .map(|k| format!("*{k}* to validate"));
@@ -83,13 +99,17 @@ impl HelpLine {
             clear_search,
             close_help,
             help,
+            new_only,
             next_match,
+            not_new_only,
             not_wrap,
             pause,
             previous_match,
             quit,
             scope,
             search,
+            search_filter,
+            not_search_filter,
             toggle_backtrace,
             toggle_summary,
             undismiss,
@@ -122,6 +142,13 @@ impl HelpLine {
             if let Some(s) = &self.previous_match {
                 parts.push(s);
             }
+            if state.is_search_filter() {
+                if let Some(s) = &self.not_search_filter {
+                    parts.push(s);
+                }
+            } else if let Some(s) = &self.search_filter {
+                parts.push(s);
+            }
             if let Some(s) = &self.clear_search {
                 parts.push(s);
             }
@@ -132,6 +159,18 @@ impl HelpLine {
             }
             return parts;
         }
+        if state.is_new_only() {
+            if let Some(s) = &self.not_new_only {
+                parts.push(s);
+            }
+        } else if state
+            .run_diff()
+            .is_some_and(|diff| !diff.new_items.is_empty())
+        {
+            if let Some(s) = &self.new_only {
+                parts.push(s);
+            }
+        }
         if state.can_be_scoped() {
             if let Some(s) = &self.scope {
                 parts.push(s);
@@ -182,6 +221,18 @@ impl HelpLine {
         state: &MissionState,
     ) -> String {
         let parts = self.applicable_parts(state);
-        format!("Hit {}", parts.join(", "))
+        let hits = format!("Hit {}", parts.join(", "));
+        match state.run_diff() {
+            Some(diff)
+                if !state.is_help() && (diff.fixed_count > 0 || !diff.new_items.is_empty()) =>
+            {
+                format!(
+                    "*{}* new, *{}* fixed since last run - {hits}",
+                    diff.new_items.len(),
+                    diff.fixed_count,
+                )
+            }
+            _ => hits,
+        }
     }
 }
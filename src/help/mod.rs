@@ -1,10 +1,12 @@
 mod examples;
+mod explain_page;
 mod help_line;
 mod help_page;
 mod list_jobs;
 
 pub use {
     examples::*,
+    explain_page::*,
     help_line::*,
     help_page::*,
     list_jobs::*,
@@ -0,0 +1,100 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    std::collections::HashMap,
+};
+
+/// Resolve the `extends` property of the jobs: every job extending
+/// another one is replaced with the extended job on which its own
+/// properties are applied, and its `additional_args` are appended
+/// to the command.
+pub fn resolve_job_extensions(jobs: &mut HashMap<String, Job>) -> Result<()> {
+    let mut resolved = HashMap::new();
+    for name in jobs.keys() {
+        let mut chain = Vec::new();
+        resolve_job(name, jobs, &mut resolved, &mut chain)?;
+    }
+    *jobs = resolved;
+    Ok(())
+}
+
+fn resolve_job(
+    name: &str,
+    jobs: &HashMap<String, Job>,
+    resolved: &mut HashMap<String, Job>,
+    chain: &mut Vec<String>,
+) -> Result<Job> {
+    if let Some(job) = resolved.get(name) {
+        return Ok(job.clone());
+    }
+    if chain.iter().any(|n| n == name) {
+        bail!("Circular job extension: {} -> {name}", chain.join(" -> "));
+    }
+    let Some(job) = jobs.get(name) else {
+        bail!(
+            "Job {:?} extends unknown job {name:?}",
+            chain.last().map_or("", String::as_str)
+        );
+    };
+    let mut resolved_job = if let Some(parent) = &job.extends {
+        chain.push(name.to_string());
+        let mut parent_job = resolve_job(parent, jobs, resolved, chain)?;
+        chain.pop();
+        parent_job.apply(job);
+        parent_job
+    } else {
+        job.clone()
+    };
+    resolved_job.extends = None;
+    if let Some(additional_args) = resolved_job.additional_args.take() {
+        resolved_job.command.extend(additional_args);
+    }
+    resolved.insert(name.to_string(), resolved_job.clone());
+    Ok(resolved_job)
+}
+
+#[test]
+fn test_resolve_job_extensions() {
+    let mut jobs: HashMap<String, Job> = toml::from_str(
+        r#"
+        [clippy]
+        command = ["cargo", "clippy", "--color", "always"]
+        need_stdout = false
+        env.RUST_LOG = "info"
+        [pedantic]
+        extends = "clippy"
+        additional_args = ["--", "-W", "clippy::pedantic"]
+        env.LEVEL = "high"
+        [pedantic-tests]
+        extends = "pedantic"
+        additional_args = ["-A", "clippy::unwrap_used"]
+        need_stdout = true
+        "#,
+    )
+    .unwrap();
+    resolve_job_extensions(&mut jobs).unwrap();
+    let job = &jobs["pedantic-tests"];
+    assert_eq!(
+        job.command.join(" "),
+        "cargo clippy --color always -- -W clippy::pedantic -A clippy::unwrap_used",
+    );
+    assert_eq!(job.need_stdout, Some(true));
+    assert_eq!(job.env.len(), 2);
+    assert_eq!(job.extends, None);
+    assert_eq!(jobs["clippy"].command.len(), 4);
+
+    // loops are detected
+    let mut jobs: HashMap<String, Job> = toml::from_str(
+        r#"
+        [a]
+        extends = "b"
+        [b]
+        extends = "a"
+        "#,
+    )
+    .unwrap();
+    assert!(resolve_job_extensions(&mut jobs).is_err());
+}
@@ -1,5 +1,9 @@
 use {
     crate::*,
+    anyhow::{
+        Result,
+        anyhow,
+    },
     schemars::JsonSchema,
     serde::Deserialize,
     std::{
@@ -11,6 +15,10 @@ use {
 /// One of the possible jobs that bacon can run
 #[derive(Debug, Default, Clone, Deserialize, PartialEq, JsonSchema)]
 pub struct Job {
+    /// Arguments appended to the command, which is convenient
+    /// to specialize the command of an extended job
+    pub additional_args: Option<Vec<String>>,
+
     /// Whether to consider that we can have a success
     /// when we have test failures
     pub allow_failures: Option<bool>,
@@ -35,6 +43,13 @@ pub struct Job {
     /// the new one as soon as it starts.
     pub background: Option<bool>,
 
+    /// The path to a baseline file (eg `.bacon-baseline.json`),
+    /// relative to the package directory.
+    ///
+    /// Items recorded in the baseline (with the `record-baseline` action)
+    /// are hidden and don't make the job fail.
+    pub baseline: Option<PathBuf>,
+
     /// The tokens making the command to execute (first one
     /// is the executable).
     #[serde(default)]
@@ -48,6 +63,10 @@ pub struct Job {
     /// `watch`.
     pub default_watch: Option<bool>,
 
+    /// Jobs which must succeed, in order, before this job's
+    /// command is run (eg `["check"]` for a test job)
+    pub depends_on: Option<Vec<ConcreteJobRef>>,
+
     /// Env vars to set for this job execution
     #[serde(default)]
     pub env: HashMap<String, String>,
@@ -55,6 +74,11 @@ pub struct Job {
     /// Whether to expand environment variables in the command
     pub expand_env_vars: Option<bool>,
 
+    /// Name of a job this one is based on: its properties are
+    /// the ones of this other job, overridden by the ones
+    /// set here
+    pub extends: Option<String>,
+
     /// Whether to insert extraneous arguments provided by bacon or end users
     ///
     /// Eg: --all-features or anything after -- in bacon incantation
@@ -161,6 +185,20 @@ impl Job {
             ..Default::default()
         }
     }
+    /// Find the job referenced by name or alias in the settings
+    pub fn from_concrete_ref(
+        concrete: &ConcreteJobRef,
+        settings: &Settings,
+    ) -> Result<Self> {
+        match &concrete.name_or_alias {
+            NameOrAlias::Alias(alias) => Ok(Self::from_alias(alias, settings)),
+            NameOrAlias::Name(name) => settings
+                .jobs
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("job not found: {name:?}")),
+        }
+    }
     pub fn allow_failures(&self) -> bool {
         self.allow_failures.unwrap_or(false)
     }
@@ -203,14 +241,17 @@ impl Job {
         &mut self,
         job: &Job,
     ) {
+        if let Some(v) = job.additional_args.as_ref() {
+            self.additional_args = Some(v.clone());
+        }
         if let Some(b) = job.allow_failures {
             self.allow_failures = Some(b);
         }
         if let Some(b) = job.allow_warnings {
             self.allow_warnings = Some(b);
         }
-        if let Some(v) = job.analyzer {
-            self.analyzer = Some(v);
+        if let Some(v) = job.analyzer.as_ref() {
+            self.analyzer = Some(v.clone());
         }
         if let Some(b) = job.apply_gitignore {
             self.apply_gitignore = Some(b);
@@ -218,18 +259,27 @@ impl Job {
         if let Some(b) = job.background {
             self.background = Some(b);
         }
+        if let Some(p) = job.baseline.as_ref() {
+            self.baseline = Some(p.clone());
+        }
         if !job.command.is_empty() {
             self.command.clone_from(&job.command);
         }
         if let Some(b) = job.default_watch {
             self.default_watch = Some(b);
         }
+        if let Some(v) = job.depends_on.as_ref() {
+            self.depends_on = Some(v.clone());
+        }
         for (k, v) in &job.env {
             self.env.insert(k.clone(), v.clone());
         }
         if let Some(b) = job.expand_env_vars {
             self.expand_env_vars = Some(b);
         }
+        if let Some(v) = job.extends.as_ref() {
+            self.extends = Some(v.clone());
+        }
         if let Some(b) = job.extraneous_args {
             self.extraneous_args = Some(b);
         }
@@ -287,17 +337,21 @@ fn test_job_apply() {
     use std::str::FromStr;
     let mut base_job = Job::default();
     let job_to_apply = Job {
+        additional_args: Some(vec!["--all-targets".to_string()]),
         allow_failures: Some(true),
         allow_warnings: Some(false),
         analyzer: Some(AnalyzerRef::Nextest),
         apply_gitignore: Some(false),
         background: Some(false),
+        baseline: Some(PathBuf::from(".bacon-baseline.json")),
         command: vec!["cargo".to_string(), "test".to_string()],
         default_watch: Some(false),
+        depends_on: Some(vec![ConcreteJobRef::from_job_name("check")]),
         env: vec![("RUST_LOG".to_string(), "debug".to_string())]
             .into_iter()
             .collect(),
         expand_env_vars: Some(false),
+        extends: Some("clippy".to_string()),
         extraneous_args: Some(false),
         hide_scrollbar: Some(true),
         ignore: vec![
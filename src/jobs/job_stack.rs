@@ -1,9 +1,6 @@
 use {
     crate::*,
-    anyhow::{
-        Result,
-        anyhow,
-    },
+    anyhow::Result,
 };
 
 /// The stack of jobs that bacon ran, allowing to get back to the previous one,
@@ -71,14 +68,7 @@ impl JobStack {
                 }
             },
         };
-        let job = match &concrete.name_or_alias {
-            NameOrAlias::Alias(alias) => Job::from_alias(alias, settings),
-            NameOrAlias::Name(name) => settings
-                .jobs
-                .get(name)
-                .ok_or_else(|| anyhow!("job not found: {name:?}"))?
-                .clone(),
-        };
+        let job = Job::from_concrete_ref(&concrete, settings)?;
         if self.entries.last() != Some(&concrete) {
             self.entries.push(concrete.clone());
         }
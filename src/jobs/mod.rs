@@ -1,13 +1,17 @@
 mod concrete_job_ref;
+mod extensions;
 mod job;
 mod job_ref;
 mod job_stack;
+mod prerequisites;
 mod scope;
 
 pub use {
     concrete_job_ref::*,
+    extensions::*,
     job::*,
     job_ref::*,
     job_stack::*,
+    prerequisites::*,
     scope::*,
 };
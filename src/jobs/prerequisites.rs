@@ -0,0 +1,76 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+};
+
+/// Resolve the prerequisites of a job, that is the jobs which must
+/// succeed, in order, before its command runs.
+///
+/// Prerequisites of prerequisites come first, and each job appears once.
+pub fn job_prerequisites(
+    concrete_job_ref: &ConcreteJobRef,
+    job: &Job,
+    settings: &Settings,
+) -> Result<Vec<(ConcreteJobRef, Job)>> {
+    let mut prerequisites = Vec::new();
+    let mut chain = vec![concrete_job_ref.clone()];
+    add_prerequisites(job, settings, &mut chain, &mut prerequisites)?;
+    Ok(prerequisites)
+}
+
+fn add_prerequisites(
+    job: &Job,
+    settings: &Settings,
+    chain: &mut Vec<ConcreteJobRef>,
+    prerequisites: &mut Vec<(ConcreteJobRef, Job)>,
+) -> Result<()> {
+    for dependency in job.depends_on.iter().flatten() {
+        if chain.contains(dependency) {
+            let chain: Vec<String> = chain.iter().map(ToString::to_string).collect();
+            bail!(
+                "Circular job dependency: {} -> {dependency}",
+                chain.join(" -> ")
+            );
+        }
+        if prerequisites.iter().any(|(r, _)| r == dependency) {
+            continue;
+        }
+        let dependency_job = Job::from_concrete_ref(dependency, settings)?;
+        chain.push(dependency.clone());
+        add_prerequisites(&dependency_job, settings, chain, prerequisites)?;
+        chain.pop();
+        prerequisites.push((dependency.clone(), dependency_job));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_job_prerequisites() {
+    let mut settings = Settings::default();
+    let job = |command: &str, depends_on: &[&str]| Job {
+        command: vec![command.to_string()],
+        depends_on: Some(depends_on.iter().map(|&s| s.into()).collect()),
+        ..Default::default()
+    };
+    settings.jobs.insert("fmt".to_string(), job("fmt", &[]));
+    settings
+        .jobs
+        .insert("check".to_string(), job("check", &["fmt"]));
+    settings
+        .jobs
+        .insert("clippy".to_string(), job("clippy", &["fmt"]));
+    settings
+        .jobs
+        .insert("test".to_string(), job("test", &["check", "clippy"]));
+    let test_ref = ConcreteJobRef::from_job_name("test");
+    let prerequisites = job_prerequisites(&test_ref, &settings.jobs["test"], &settings).unwrap();
+    let names: Vec<String> = prerequisites.iter().map(|(r, _)| r.to_string()).collect();
+    assert_eq!(names, ["fmt", "check", "clippy"]);
+    settings
+        .jobs
+        .insert("fmt".to_string(), job("fmt", &["test"]));
+    assert!(job_prerequisites(&test_ref, &settings.jobs["test"], &settings).is_err());
+}
@@ -9,10 +9,11 @@ mod export;
 mod help;
 mod ignorer;
 mod jobs;
+#[cfg(unix)]
+mod lsp;
 mod mission;
 mod result;
 mod search;
-#[cfg(unix)]
 mod socket;
 mod sound;
 mod tty;
@@ -33,6 +34,7 @@ pub use {
     mission::*,
     result::*,
     search::*,
+    socket::*,
     sound::*,
     tty::*,
     tui::*,
@@ -40,7 +42,7 @@ pub use {
 };
 
 #[cfg(unix)]
-pub use socket::*;
+pub use lsp::*;
 
 #[macro_use]
 extern crate cli_log;
@@ -0,0 +1,157 @@
+use {
+    crate::*,
+    rustc_hash::FxHashMap,
+    serde::Serialize,
+    serde_json::Value,
+    std::{
+        fs,
+        path::PathBuf,
+    },
+};
+
+/// How the `character` of positions is counted, as agreed with the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionEncoding {
+    /// UTF-16 code units, the LSP default
+    #[default]
+    Utf16,
+    /// Unicode code points, as our columns are
+    Utf32,
+}
+
+impl PositionEncoding {
+    /// Choose the encoding from the capabilities sent by the client
+    /// in its `initialize` request
+    pub fn negotiate(capabilities: &Value) -> Self {
+        let accepts_utf32 = capabilities["general"]["positionEncodings"]
+            .as_array()
+            .is_some_and(|encodings| encodings.iter().any(|e| e == "utf-32"));
+        if accepts_utf32 {
+            Self::Utf32
+        } else {
+            Self::Utf16
+        }
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf16 => "utf-16",
+            Self::Utf32 => "utf-32",
+        }
+    }
+}
+
+/// A position in a text document, zero based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A LSP diagnostic, as published in `textDocument/publishDiagnostics`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    /// 1 for errors, 2 for warnings, 3 for information
+    pub severity: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub source: &'static str,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Build the diagnostic of a report item, if it has a location
+    pub fn of_item(item: Item<'_>) -> Option<(PathBuf, Self)> {
+        let location: Location = item.location()?.parse().ok()?;
+        let title = item
+            .lines()
+            .iter()
+            .find(|line| matches!(line.line_type, LineType::Title(_)));
+        let severity = match title.map(|line| line.line_type) {
+            Some(LineType::Title(Kind::Error | Kind::TestFail)) => 1,
+            Some(LineType::Title(Kind::Warning)) => 2,
+            _ => 3,
+        };
+        let message = title
+            .and_then(|line| line.title_message())
+            .unwrap_or("")
+            .to_string();
+        let position = Position {
+            line: location.line.saturating_sub(1),
+            character: location.column.unwrap_or(1).saturating_sub(1),
+        };
+        let diagnostic = Self {
+            range: Range {
+                start: position,
+                end: position,
+            },
+            severity,
+            code: item.diag_type().map(|s| s.to_string()),
+            source: "bacon",
+            message,
+        };
+        Some((location.path, diagnostic))
+    }
+}
+
+/// Build the diagnostics of a report, grouped by absolute file path
+pub fn report_diagnostics(
+    report: &Report,
+    context: &Context,
+    encoding: PositionEncoding,
+) -> FxHashMap<PathBuf, Vec<Diagnostic>> {
+    let mut diagnostics: FxHashMap<PathBuf, Vec<Diagnostic>> = FxHashMap::default();
+    for item in Item::items_of(&report.lines) {
+        if let Some((path, diagnostic)) = Diagnostic::of_item(item) {
+            let path = context.make_absolute(path);
+            diagnostics.entry(path).or_default().push(diagnostic);
+        }
+    }
+    if encoding == PositionEncoding::Utf16 {
+        for (path, file_diagnostics) in &mut diagnostics {
+            // without the file, columns can't be converted and are left as is
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let lines: Vec<&str> = content.lines().collect();
+            for diagnostic in file_diagnostics {
+                for position in [&mut diagnostic.range.start, &mut diagnostic.range.end] {
+                    if let Some(line) = lines.get(position.line) {
+                        position.character = utf16_column(line, position.character);
+                    }
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Convert a column counted in chars into one counted in UTF-16 code units
+fn utf16_column(
+    line: &str,
+    char_column: usize,
+) -> usize {
+    let mut units = 0;
+    let mut chars = 0;
+    for c in line.chars().take(char_column) {
+        units += c.len_utf16();
+        chars += 1;
+    }
+    // a column past the end of the line is kept past the end
+    units + char_column - chars
+}
+
+#[test]
+fn test_utf16_column() {
+    assert_eq!(utf16_column("let a = 3;", 4), 4);
+    assert_eq!(utf16_column("let é = 3;", 6), 6);
+    assert_eq!(utf16_column("let 🦀 = 3;", 4), 4);
+    assert_eq!(utf16_column("let 🦀 = 3;", 6), 7);
+    assert_eq!(utf16_column("🦀", 3), 4);
+}
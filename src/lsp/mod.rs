@@ -0,0 +1,9 @@
+mod diagnostic;
+mod server;
+mod transport;
+
+pub use {
+    diagnostic::*,
+    server::*,
+    transport::*,
+};
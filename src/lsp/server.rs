@@ -0,0 +1,262 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    rustc_hash::FxHashSet,
+    serde_json::{
+        Value,
+        json,
+    },
+    std::{
+        fs,
+        io,
+        path::{
+            Path,
+            PathBuf,
+        },
+        process::{
+            Child,
+            Command,
+            Stdio,
+        },
+        thread,
+        time::Duration,
+    },
+    termimad::crossbeam::channel::{
+        select,
+        unbounded,
+    },
+};
+
+/// How long to wait for a bacon launched by the LSP server to listen
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A headless bacon launched by the LSP server because none was
+/// listening in the package, killed with the server
+struct LaunchedBacon {
+    child: Child,
+    socket_path: PathBuf,
+}
+
+impl Drop for LaunchedBacon {
+    fn drop(&mut self) {
+        debug!("killing launched bacon");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        // the killed bacon couldn't remove its socket file
+        let _ = fs::remove_file(&self.socket_path);
+    }
+}
+
+/// A LSP server publishing the diagnostics of a listening bacon
+struct LspServer<'c> {
+    context: &'c Context,
+    stdout: io::Stdout,
+    /// Whether the client sent the `initialize` request
+    initialized: bool,
+    position_encoding: PositionEncoding,
+    /// Files for which non empty diagnostics were published
+    published: FxHashSet<PathBuf>,
+}
+
+/// Run a LSP server on stdin and stdout, publishing the diagnostics of
+/// the bacon instance listening in the package, or of a headless one
+/// launched for the occasion
+pub fn run_lsp(context: &Context) -> Result<()> {
+    let _launched = if socket::query(context, Query::Status).is_ok() {
+        info!("attaching to the listening bacon");
+        None
+    } else {
+        Some(launch_bacon(context)?)
+    };
+    let (event_tx, event_rx) = unbounded();
+    let events = socket::subscribe(context)?;
+    thread::spawn(move || {
+        for event in events {
+            if event_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    let (message_tx, message_rx) = unbounded();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            match read_message(&mut stdin) {
+                Ok(Some(message)) => {
+                    if message_tx.send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    break;
+                }
+                Err(e) => {
+                    warn!("invalid LSP message: {e}");
+                    break;
+                }
+            }
+        }
+    });
+    let mut server = LspServer {
+        context,
+        stdout: io::stdout(),
+        initialized: false,
+        position_encoding: PositionEncoding::default(),
+        published: Default::default(),
+    };
+    loop {
+        select! {
+            recv(message_rx) -> message => {
+                let Ok(message) = message else {
+                    info!("LSP client left");
+                    return Ok(());
+                };
+                if !server.handle_message(&message)? {
+                    return Ok(());
+                }
+            }
+            recv(event_rx) -> event => {
+                let Ok(event) = event else {
+                    bail!("bacon stopped");
+                };
+                if let MissionEvent::TaskFinished(_) = event? {
+                    server.publish_report()?;
+                }
+            }
+        }
+    }
+}
+
+/// Launch a headless bacon listening on the socket, with the same arguments
+fn launch_bacon(context: &Context) -> Result<LaunchedBacon> {
+    let args = std::env::args_os().skip(1).filter(|arg| {
+        !["--lsp", "--listen", "--no-listen", "--headless"].contains(&&*arg.to_string_lossy())
+    });
+    let child = Command::new(std::env::current_exe()?)
+        .args(args)
+        .args(["--headless", "--listen"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let launched = LaunchedBacon {
+        child,
+        socket_path: context.unix_socket_path(),
+    };
+    let step = Duration::from_millis(100);
+    let mut waited = Duration::ZERO;
+    while waited < LAUNCH_TIMEOUT {
+        if socket::query(context, Query::Status).is_ok() {
+            info!("launched bacon is listening");
+            return Ok(launched);
+        }
+        thread::sleep(step);
+        waited += step;
+    }
+    bail!("The launched bacon isn't listening");
+}
+
+impl LspServer<'_> {
+    fn send(
+        &mut self,
+        message: Value,
+    ) -> Result<()> {
+        write_message(&mut self.stdout, &message)
+    }
+    /// Handle a message from the client, return false when the server must exit
+    fn handle_message(
+        &mut self,
+        message: &Value,
+    ) -> Result<bool> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+        debug!("LSP message: {method:?}");
+        match (method, id) {
+            ("initialize", Some(id)) => {
+                self.position_encoding =
+                    PositionEncoding::negotiate(&message["params"]["capabilities"]);
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "capabilities": {
+                            "positionEncoding": self.position_encoding.name(),
+                        },
+                        "serverInfo": {
+                            "name": "bacon",
+                            "version": env!("CARGO_PKG_VERSION"),
+                        },
+                    },
+                }))?;
+                self.initialized = true;
+                self.publish_report()?;
+            }
+            ("shutdown", Some(id)) => {
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": null,
+                }))?;
+            }
+            ("exit", None) => {
+                return Ok(false);
+            }
+            (_, Some(id)) => {
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32601,
+                        "message": format!("unsupported method: {method}"),
+                    },
+                }))?;
+            }
+            _ => {} // notifications we don't care about
+        }
+        Ok(true)
+    }
+    /// Get the current report of bacon and publish its diagnostics,
+    /// clearing the ones of the files which are now clean
+    fn publish_report(&mut self) -> Result<()> {
+        if !self.initialized {
+            return Ok(());
+        }
+        let report = match socket::query(self.context, Query::Report)? {
+            Response::Report(report) => report,
+            response => bail!("unexpected response: {response:?}"),
+        };
+        let diagnostics = report
+            .map(|report| report_diagnostics(&report, self.context, self.position_encoding))
+            .unwrap_or_default();
+        let cleaned: Vec<PathBuf> = self
+            .published
+            .drain()
+            .filter(|path| !diagnostics.contains_key(path))
+            .collect();
+        for path in cleaned {
+            self.publish(&path, &[])?;
+        }
+        for (path, file_diagnostics) in &diagnostics {
+            self.publish(path, file_diagnostics)?;
+        }
+        self.published = diagnostics.into_keys().collect();
+        Ok(())
+    }
+    fn publish(
+        &mut self,
+        path: &Path,
+        diagnostics: &[Diagnostic],
+    ) -> Result<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": path_to_uri(path),
+                "diagnostics": diagnostics,
+            },
+        }))
+    }
+}
@@ -0,0 +1,69 @@
+use {
+    anyhow::{
+        Context as _,
+        Result,
+        bail,
+    },
+    serde_json::Value,
+    std::io::{
+        BufRead,
+        Write,
+    },
+};
+
+/// Read a LSP message (headers then JSON body) or return `None`
+/// when the input is closed
+pub fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .context("invalid Content-Length")?,
+                );
+            }
+        }
+    }
+    let Some(content_length) = content_length else {
+        bail!("missing Content-Length header");
+    };
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Write a LSP message, with its header
+pub fn write_message<W: Write>(
+    w: &mut W,
+    message: &Value,
+) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(w, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    w.flush()?;
+    Ok(())
+}
+
+#[test]
+fn test_message_round_trip() {
+    let message = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "initialized",
+        "params": {},
+    });
+    let mut buffer = Vec::new();
+    write_message(&mut buffer, &message).unwrap();
+    let mut reader = std::io::Cursor::new(buffer);
+    assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+}
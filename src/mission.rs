@@ -4,7 +4,10 @@ use {
     rustc_hash::FxHashSet,
     std::{
         collections::HashMap,
-        path::PathBuf,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
 };
 
@@ -20,9 +23,30 @@ pub struct Mission<'s> {
     pub job: Job,
     pub paths_to_watch: Vec<PathBuf>,
     pub settings: &'s Settings,
+    /// Missions of the jobs which must succeed, in order, before
+    /// the one of this mission (see `Job::depends_on`)
+    pub prerequisites: Vec<Mission<'s>>,
 }
 
 impl Mission<'_> {
+    /// The mission of the given stage: one of the prerequisites,
+    /// in order, then the mission itself
+    pub fn stage(
+        &self,
+        stage: usize,
+    ) -> &Self {
+        self.prerequisites.get(stage).unwrap_or(self)
+    }
+    /// The paths to watch for the mission and its prerequisites
+    pub fn all_paths_to_watch(&self) -> Vec<PathBuf> {
+        let mut paths = self.paths_to_watch.clone();
+        for path in self.prerequisites.iter().flat_map(|p| &p.paths_to_watch) {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
     /// Return an Ignorer according to the job's settings
     pub fn ignorer(&self) -> IgnorerSet {
         let mut set = IgnorerSet::default();
@@ -66,19 +90,19 @@ impl Mission<'_> {
         &self,
         path: PathBuf,
     ) -> PathBuf {
-        if path.is_absolute() {
-            return path;
-        }
-        // There's a small mess here. Cargo tends to make paths relative
-        // not to the package or work directory but to the workspace, contrary
-        // to any sane tool. We have to guess.
-        if let Some(workspace) = &self.workspace_directory {
-            let workspace_joined = workspace.join(&path);
-            if workspace_joined.exists() {
-                return workspace_joined;
-            }
-        }
-        self.package_directory.join(&path)
+        absolute_report_path(
+            path,
+            &self.package_directory,
+            self.workspace_directory.as_deref(),
+        )
+    }
+
+    /// The path to the baseline file of the job, if any
+    pub fn baseline_path(&self) -> Option<PathBuf> {
+        self.job
+            .baseline
+            .as_ref()
+            .map(|path| self.package_directory.join(path))
     }
 
     /// build (and doesn't call) the external cargo command
@@ -241,7 +265,7 @@ impl Mission<'_> {
     }
 
     pub fn analyzer(&self) -> AnalyzerRef {
-        self.job.analyzer.unwrap_or_default()
+        self.job.analyzer.clone().unwrap_or_default()
     }
 
     pub fn ignored_lines_patterns(&self) -> Option<&Vec<LinePattern>> {
@@ -280,3 +304,25 @@ fn merge_features(
     }
     features.iter().copied().collect::<Vec<&str>>().join(",")
 }
+
+/// Make a path, as found in a report, absolute, guessing whether it's
+/// relative to the workspace or to the package
+pub fn absolute_report_path(
+    path: PathBuf,
+    package_directory: &Path,
+    workspace_directory: Option<&Path>,
+) -> PathBuf {
+    if path.is_absolute() {
+        return path;
+    }
+    // There's a small mess here. Cargo tends to make paths relative
+    // not to the package or work directory but to the workspace, contrary
+    // to any sane tool. We have to guess.
+    if let Some(workspace) = workspace_directory {
+        let workspace_joined = workspace.join(&path);
+        if workspace_joined.exists() {
+            return workspace_joined;
+        }
+    }
+    package_directory.join(&path)
+}
@@ -0,0 +1,145 @@
+use {
+    crate::*,
+    anyhow::{
+        Context,
+        Result,
+    },
+    rustc_hash::{
+        FxHashMap,
+        FxHashSet,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        fs,
+        path::Path,
+    },
+};
+
+/// The warnings accepted at some point, which are hidden and not
+/// considered as failures, as stored in a baseline file (eg
+/// `.bacon-baseline.json`) meant to be committed.
+///
+/// Errors and test failures are never baselined.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Baseline {
+    pub fingerprints: Vec<ItemFingerprint>,
+}
+
+impl Baseline {
+    /// Read the baseline file, a missing file being an empty baseline
+    pub fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid baseline file {}", path.display()))
+    }
+    pub fn write(
+        &self,
+        path: &Path,
+    ) -> Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(path, json)?;
+        Ok(())
+    }
+    /// Build the baseline of all the warnings of the report, including
+    /// the dismissed and baselined ones
+    pub fn of_report(report: &Report) -> Self {
+        let mut fingerprints: Vec<ItemFingerprint> = warning_fingerprints(report)
+            .into_iter()
+            .map(|(_, fingerprint)| fingerprint)
+            .collect();
+        let mut dismissed_lines = report.dismissed_lines.clone();
+        dismissed_lines.sort_by_key(|line| line.item_idx);
+        fingerprints.extend(
+            warning_fingerprints(&Report::new(dismissed_lines))
+                .into_iter()
+                .map(|(_, fingerprint)| fingerprint),
+        );
+        fingerprints.extend(report.baselined.iter().cloned());
+        fingerprints.sort();
+        Self { fingerprints }
+    }
+    /// Remove from the report the warnings which are in the baseline
+    /// (each entry of the baseline hiding at most one item)
+    pub fn remove_baselined_items(
+        &self,
+        report: &mut Report,
+    ) {
+        let mut unmatched: FxHashMap<&str, usize> = FxHashMap::default();
+        for fingerprint in &self.fingerprints {
+            *unmatched.entry(fingerprint).or_default() += 1;
+        }
+        let mut baselined_idxs = Vec::new();
+        for (item_idx, fingerprint) in warning_fingerprints(report) {
+            if let Some(count) = unmatched.get_mut(fingerprint.as_str()) {
+                if *count > 0 {
+                    *count -= 1;
+                    baselined_idxs.push(item_idx);
+                    report.baselined.push(fingerprint);
+                }
+            }
+        }
+        if baselined_idxs.is_empty() {
+            return;
+        }
+        report
+            .lines
+            .retain(|line| !baselined_idxs.contains(&line.item_idx));
+        report.lines_changed();
+        info!("{} baselined items hidden", baselined_idxs.len());
+    }
+}
+
+/// The fingerprints of the warnings of the report, with their item index
+fn warning_fingerprints(report: &Report) -> Vec<(usize, ItemFingerprint)> {
+    let warning_idxs: FxHashSet<usize> = report
+        .lines
+        .iter()
+        .filter(|line| line.line_type == LineType::Title(Kind::Warning))
+        .map(|line| line.item_idx)
+        .collect();
+    report
+        .item_fingerprints()
+        .into_iter()
+        .filter(|(item_idx, _)| warning_idxs.contains(item_idx))
+        .collect()
+}
+
+#[test]
+fn test_baseline() {
+    let line = |item_idx, content: &str| Line {
+        item_idx,
+        line_type: LineType::Title(if content.starts_with("error") {
+            Kind::Error
+        } else {
+            Kind::Warning
+        }),
+        content: TLine::from_raw(content.to_string()),
+    };
+    let old_report = Report::new(vec![
+        line(1, "warning: a"),
+        line(2, "warning: a"),
+        line(3, "error: c"),
+    ]);
+    let baseline = Baseline::of_report(&old_report);
+    let mut report = Report::new(vec![
+        line(1, "warning: a"),
+        line(2, "warning: b"),
+        line(3, "warning: a"),
+        line(4, "warning: a"),
+        line(5, "error: c"),
+    ]);
+    baseline.remove_baselined_items(&mut report);
+    assert_eq!(report.stats.warnings, 2);
+    assert_eq!(report.stats.errors, 1);
+    assert_eq!(report.baselined.len(), 2);
+    let idxs: Vec<usize> = report.lines.iter().map(|line| line.item_idx).collect();
+    assert_eq!(idxs, vec![2, 4, 5]);
+    assert_eq!(Baseline::of_report(&report).fingerprints.len(), 4);
+}
@@ -62,6 +62,9 @@ impl CommandOutput {
     pub fn len(&self) -> usize {
         self.lines.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
     pub fn clear(&mut self) {
         self.lines.clear();
     }
@@ -10,6 +10,7 @@ use {
 
 /// what we get from the execution of a command
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum CommandResult {
     /// a trustable report with errors and warnings computed
     Report(Report),
@@ -1,46 +1,244 @@
 use {
     crate::*,
+    anyhow::{
+        Result,
+        anyhow,
+        bail,
+    },
+    lazy_regex::regex::Regex,
     rustc_hash::FxHashSet,
-    std::fmt,
+    schemars::JsonSchema,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        fmt,
+        fs,
+        path::PathBuf,
+    },
 };
 
+/// The dismissals, either declared in configuration or done by the
+/// user, in which case they're saved in the project's dismissals file
 #[derive(Default, Debug)]
 pub struct Filter {
     dismissals: Vec<Dismissal>,
+    /// the dismissals declared in configuration, which aren't saved
+    configured: Vec<Dismissal>,
+    /// where the dismissals done by the user are saved, if anywhere
+    path: Option<PathBuf>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum Dismissal {
+    /// The exact location of the item, eg `src/main.rs:12:5`
     Location(String),
+    /// The type of the diagnostic, eg `dead_code`
     DiagType(String),
+    /// A regular expression matched against the title of the item,
+    /// eg `^warning: field .* is never read`
+    TitleRegex(String),
+    /// A glob matched against the file of the item, eg `src/generated/**`
+    FileGlob(String),
+    /// Criteria which must all match, eg a type in some files
+    Combined(CombinedDismissal),
+}
+
+/// The criteria of a combined dismissal, each one optional
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CombinedDismissal {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diag_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_glob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_regex: Option<String>,
+}
+
+/// A dismissal, with its pattern compiled
+enum DismissalMatcher<'d> {
+    Location(&'d str),
+    DiagType(&'d str),
+    TitleRegex(Regex),
+    FileGlob(glob::Pattern),
+    All(Vec<DismissalMatcher<'d>>),
 }
 
 impl Dismissal {
+    fn matcher(&self) -> Result<DismissalMatcher<'_>> {
+        Ok(match self {
+            Self::Location(v) => DismissalMatcher::Location(v),
+            Self::DiagType(v) => DismissalMatcher::DiagType(v),
+            Self::TitleRegex(v) => title_regex_matcher(v)?,
+            Self::FileGlob(v) => file_glob_matcher(v)?,
+            Self::Combined(combined) => {
+                let mut matchers = Vec::new();
+                if let Some(v) = &combined.diag_type {
+                    matchers.push(DismissalMatcher::DiagType(v));
+                }
+                if let Some(v) = &combined.file_glob {
+                    matchers.push(file_glob_matcher(v)?);
+                }
+                if let Some(v) = &combined.title_regex {
+                    matchers.push(title_regex_matcher(v)?);
+                }
+                if matchers.is_empty() {
+                    bail!("A combined dismissal needs at least one criterion");
+                }
+                DismissalMatcher::All(matchers)
+            }
+        })
+    }
+    /// Check the patterns of the dismissal are valid
+    pub fn check(&self) -> Result<()> {
+        self.matcher().map(|_| ())
+    }
     /// Return true if the item must be dismissed
-    pub fn applies_to(
+    ///
+    /// The patterns are compiled at every call: to filter a report,
+    /// use `Filter::remove_dismissed_lines`
+    #[cfg(test)]
+    fn applies_to(
         &self,
         item: Item<'_>,
     ) -> bool {
-        match self {
-            Self::Location(v) => item.location().is_some_and(|loc| loc == v),
-            Self::DiagType(v) => item.diag_type().is_some_and(|loc| loc == v),
-        }
+        self.matcher().is_ok_and(|matcher| matcher.applies_to(item))
     }
     pub fn undo_action(&self) -> Action {
         match self {
             Self::Location(v) => Action::UndismissLocation(v.clone()),
             Self::DiagType(v) => Action::UndismissDiagType(v.clone()),
+            Self::TitleRegex(v) => Action::UndismissTitleRegex(v.clone()),
+            Self::FileGlob(v) => Action::UndismissFileGlob(v.clone()),
+            Self::Combined(v) => Action::UndismissCombined(v.clone()),
+        }
+    }
+}
+
+fn title_regex_matcher(v: &str) -> Result<DismissalMatcher<'_>> {
+    Regex::new(v)
+        .map(DismissalMatcher::TitleRegex)
+        .map_err(|e| anyhow!("Invalid title regex {v:?}: {e}"))
+}
+
+fn file_glob_matcher(v: &str) -> Result<DismissalMatcher<'_>> {
+    glob::Pattern::new(v)
+        .map(DismissalMatcher::FileGlob)
+        .map_err(|e| anyhow!("Invalid file glob {v:?}: {e}"))
+}
+
+impl DismissalMatcher<'_> {
+    fn applies_to(
+        &self,
+        item: Item<'_>,
+    ) -> bool {
+        match self {
+            Self::Location(v) => item.location().is_some_and(|loc| loc == *v),
+            Self::DiagType(v) => item.diag_type().is_some_and(|dt| dt == *v),
+            Self::TitleRegex(regex) => item
+                .lines()
+                .iter()
+                .find(|line| matches!(line.line_type, LineType::Title(_)))
+                .is_some_and(|line| regex.is_match(&line.content.to_raw())),
+            Self::FileGlob(pattern) => item
+                .location()
+                .and_then(|location| location.parse::<Location>().ok())
+                .is_some_and(|location| pattern.matches_path(&location.path)),
+            Self::All(matchers) => matchers.iter().all(|matcher| matcher.applies_to(item)),
         }
     }
 }
 
 impl Filter {
+    /// Build the filter from the configured dismissals and the ones
+    /// saved in the dismissals file, if it exists
+    pub fn load(
+        path: PathBuf,
+        configured: &[Dismissal],
+    ) -> Self {
+        let mut filter = Self {
+            path: Some(path),
+            ..Default::default()
+        };
+        filter.set_configured(configured);
+        let saved = filter
+            .path
+            .as_ref()
+            .filter(|path| path.exists())
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| Ok(serde_json::from_str::<Vec<Dismissal>>(&json)?))
+            });
+        match saved {
+            Some(Ok(saved)) => {
+                for dismissal in saved {
+                    if !filter.dismissals.contains(&dismissal) {
+                        filter.dismissals.push(dismissal);
+                    }
+                }
+            }
+            Some(Err(e)) => {
+                warn!("failed to read the saved dismissals: {e}");
+            }
+            None => {}
+        }
+        filter
+    }
+    /// Apply the dismissals declared in configuration, for example
+    /// when it's reloaded, removing the ones which were only declared
+    /// in the previous configuration
+    pub fn set_configured(
+        &mut self,
+        configured: &[Dismissal],
+    ) {
+        let previous = std::mem::replace(&mut self.configured, configured.to_vec());
+        self.dismissals
+            .retain(|d| !previous.contains(d) || configured.contains(d));
+        for dismissal in configured {
+            if !self.dismissals.contains(dismissal) {
+                self.dismissals.push(dismissal.clone());
+            }
+        }
+    }
+    /// Save the dismissals done by the user, so that they're applied
+    /// on next launches
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved: Vec<&Dismissal> = self
+            .dismissals
+            .iter()
+            .filter(|d| !self.configured.contains(d))
+            .collect();
+        let res = if saved.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)
+            } else {
+                Ok(())
+            }
+        } else {
+            serde_json::to_string_pretty(&saved)
+                .map_err(std::io::Error::other)
+                .and_then(|json| fs::write(path, json))
+        };
+        if let Err(e) = res {
+            warn!("failed to save dismissals in {}: {e}", path.display());
+        }
+    }
+    pub fn dismissals(&self) -> &[Dismissal] {
+        &self.dismissals
+    }
     pub fn add(
         &mut self,
         dismissal: Dismissal,
     ) {
         if !self.dismissals.contains(&dismissal) {
             self.dismissals.push(dismissal);
+            self.save();
         }
     }
     pub fn remove(
@@ -48,15 +246,33 @@ impl Filter {
         dismissal: &Dismissal,
     ) {
         self.dismissals.retain(|d| d != dismissal);
+        self.save();
+    }
+    /// Remove all dismissals, including the configured ones (until
+    /// the configuration is reloaded)
+    pub fn clear(&mut self) {
+        self.dismissals.clear();
+        self.save();
     }
     pub fn remove_dismissed_lines(
         &self,
         report: &mut Report,
     ) -> bool {
+        let matchers: Vec<DismissalMatcher> = self
+            .dismissals
+            .iter()
+            .filter_map(|dismissal| match dismissal.matcher() {
+                Ok(matcher) => Some(matcher),
+                Err(e) => {
+                    warn!("ignoring dismissal: {e}");
+                    None
+                }
+            })
+            .collect();
         let mut dismissed_item_idxs = FxHashSet::default();
         for item in Item::items_of(&report.lines) {
-            for dismissal in &self.dismissals {
-                if dismissal.applies_to(item) {
+            for matcher in &matchers {
+                if matcher.applies_to(item) {
                     dismissed_item_idxs.insert(item.item_idx());
                     break;
                 }
@@ -109,6 +325,85 @@ impl fmt::Display for Dismissal {
         match self {
             Self::Location(v) => write!(f, "location: {v}"),
             Self::DiagType(v) => write!(f, "diag_type: {v}"),
+            Self::TitleRegex(v) => write!(f, "title_regex: {v}"),
+            Self::FileGlob(v) => write!(f, "file_glob: {v}"),
+            Self::Combined(v) => write!(f, "{v}"),
         }
     }
 }
+
+impl fmt::Display for CombinedDismissal {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let criteria = [
+            ("diag_type", &self.diag_type),
+            ("file_glob", &self.file_glob),
+            ("title_regex", &self.title_regex),
+        ];
+        let mut criteria = criteria
+            .iter()
+            .filter_map(|(name, value)| value.as_ref().map(|v| (name, v)));
+        if let Some((name, value)) = criteria.next() {
+            write!(f, "{name}: {value}")?;
+        }
+        for (name, value) in criteria {
+            write!(f, " and {name}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dismissal_kinds() {
+    let lines = vec![
+        Line {
+            item_idx: 1,
+            line_type: LineType::Title(Kind::Warning),
+            content: TLine::from_tty("\u{1b}[33mwarning\u{1b}[0m: field `a` is never read"),
+        },
+        Line {
+            item_idx: 1,
+            line_type: LineType::Location,
+            content: TLine::from_raw("  --> src/generated/model.rs:3:5".to_string()),
+        },
+    ];
+    let item = Item::items_of(&lines)[0];
+    assert!(
+        Dismissal::TitleRegex(r"^warning: field .* is never read$".to_string()).applies_to(item)
+    );
+    assert!(!Dismissal::TitleRegex("^error".to_string()).applies_to(item));
+    assert!(Dismissal::FileGlob("src/generated/**".to_string()).applies_to(item));
+    assert!(!Dismissal::FileGlob("tests/**".to_string()).applies_to(item));
+    assert!(Dismissal::FileGlob("[".to_string()).check().is_err());
+    let combined = |diag_type: &str, file_glob: &str| {
+        Dismissal::Combined(CombinedDismissal {
+            diag_type: Some(diag_type.to_string()),
+            file_glob: Some(file_glob.to_string()),
+            title_regex: None,
+        })
+    };
+    assert!(combined("field `a` is never read", "src/generated/**").applies_to(item));
+    assert!(!combined("field `a` is never read", "tests/**").applies_to(item));
+    assert!(!combined("dead_code", "src/generated/**").applies_to(item));
+    assert!(
+        Dismissal::Combined(CombinedDismissal::default())
+            .check()
+            .is_err()
+    );
+}
+
+#[test]
+fn test_set_configured() {
+    let a = Dismissal::DiagType("dead_code".to_string());
+    let b = Dismissal::DiagType("unused_variables".to_string());
+    let c = Dismissal::Location("src/main.rs:2:9".to_string());
+    let mut filter = Filter::default();
+    filter.set_configured(&[a.clone(), b.clone()]);
+    filter.add(c.clone()); // done by the user
+    filter.set_configured(std::slice::from_ref(&b));
+    assert_eq!(filter.dismissals(), &[b, c.clone()]);
+    filter.set_configured(&[]);
+    assert_eq!(filter.dismissals(), &[c]);
+}
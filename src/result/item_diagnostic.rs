@@ -0,0 +1,192 @@
+use {
+    crate::*,
+    anyhow::{
+        Result,
+        bail,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        fs,
+        path::PathBuf,
+    },
+};
+
+/// The structured diagnostic of a report item, when the analyzer
+/// could read more than the rendered lines (eg from the JSON output
+/// of cargo)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemDiagnostic {
+    /// "error", "warning", "note", "help", etc.
+    pub level: String,
+    pub message: String,
+    /// The code of the diagnostic, eg "E0308" or "clippy::needless_return"
+    pub code: Option<String>,
+    pub spans: Vec<ItemSpan>,
+    /// Sub-diagnostics, usually notes and suggestions
+    pub children: Vec<ItemDiagnostic>,
+}
+
+/// A part of a source file a diagnostic refers to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemSpan {
+    /// The path, as given by the tool (cargo makes it relative to the workspace)
+    pub file_name: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    /// The source text of the span, when the diagnostic was made
+    #[serde(default)]
+    pub text: String,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    /// The text which should replace the span
+    pub suggested_replacement: Option<String>,
+    /// Whether the suggested replacement can be applied without human check
+    pub machine_applicable: bool,
+}
+
+/// A change to apply to a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    pub path: PathBuf,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    /// The text expected between the offsets, as when the analysis was done
+    pub original: String,
+    pub text: String,
+}
+
+impl ItemDiagnostic {
+    /// Return the suggestions which can be applied without human check,
+    /// each one being a group of replacements to apply together
+    pub fn machine_applicable_suggestions(&self) -> Vec<Vec<&ItemSpan>> {
+        let mut suggestions = Vec::new();
+        self.collect_suggestions(&mut suggestions);
+        suggestions
+    }
+    fn collect_suggestions<'d>(
+        &'d self,
+        suggestions: &mut Vec<Vec<&'d ItemSpan>>,
+    ) {
+        let spans: Vec<&ItemSpan> = self
+            .spans
+            .iter()
+            .filter(|span| span.machine_applicable && span.suggested_replacement.is_some())
+            .collect();
+        if !spans.is_empty() {
+            suggestions.push(spans);
+        }
+        for child in &self.children {
+            child.collect_suggestions(suggestions);
+        }
+    }
+    /// Return the replacements of the machine applicable suggestions,
+    /// with absolute paths, skipping the suggestions which would overlap
+    /// previous ones
+    pub fn fix_replacements(
+        &self,
+        mission: &Mission,
+    ) -> Vec<Replacement> {
+        let mut replacements: Vec<Replacement> = Vec::new();
+        for suggestion in self.machine_applicable_suggestions() {
+            let candidates: Vec<Replacement> = suggestion
+                .into_iter()
+                .map(|span| Replacement {
+                    path: mission.make_absolute(PathBuf::from(&span.file_name)),
+                    byte_start: span.byte_start,
+                    byte_end: span.byte_end,
+                    original: span.text.clone(),
+                    text: span.suggested_replacement.clone().unwrap_or_default(),
+                })
+                .collect();
+            let overlaps = candidates.iter().any(|c| {
+                replacements
+                    .iter()
+                    .any(|r| r.path == c.path && r.overlaps(c))
+            });
+            if !overlaps {
+                replacements.extend(candidates);
+            }
+        }
+        replacements
+    }
+}
+
+impl Replacement {
+    fn overlaps(
+        &self,
+        other: &Self,
+    ) -> bool {
+        (self.byte_start < other.byte_end && other.byte_start < self.byte_end)
+            || self.byte_start == other.byte_start
+    }
+}
+
+/// Write the replacements in the source files, returning the number
+/// of modified files.
+///
+/// Nothing is written if a file doesn't contain, at the offsets of a
+/// replacement, the text it had when the analysis was done.
+pub fn apply_replacements(mut replacements: Vec<Replacement>) -> Result<usize> {
+    // replacements are applied from the end of the file so that
+    // the offsets of the next ones stay valid
+    replacements.sort_by(|a, b| a.path.cmp(&b.path).then(b.byte_start.cmp(&a.byte_start)));
+    let mut contents = Vec::new();
+    for file_replacements in replacements.chunk_by(|a, b| a.path == b.path) {
+        let path = &file_replacements[0].path;
+        let mut content = fs::read(path)?;
+        for replacement in file_replacements {
+            let range = replacement.byte_start..replacement.byte_end;
+            if content.get(range.clone()) != Some(replacement.original.as_bytes()) {
+                bail!(
+                    "{} changed since the analysis, run the job again",
+                    path.display()
+                );
+            }
+            content.splice(range, replacement.text.bytes());
+        }
+        contents.push((path, content, file_replacements.len()));
+    }
+    for (path, content, count) in &contents {
+        fs::write(path, content)?;
+        info!("applied {count} replacement(s) to {}", path.display());
+    }
+    Ok(contents.len())
+}
+
+#[test]
+fn test_apply_replacements() {
+    let path = std::env::temp_dir().join(format!("bacon-fix-test-{}.rs", std::process::id()));
+    fs::write(&path, "fn main() { return; let x = 3; }").unwrap();
+    let replacement = |byte_start, byte_end, original: &str, text: &str| Replacement {
+        path: path.clone(),
+        byte_start,
+        byte_end,
+        original: original.to_string(),
+        text: text.to_string(),
+    };
+    let modified = apply_replacements(vec![
+        replacement(12, 19, "return;", ""),
+        replacement(24, 25, "x", "_x"),
+    ])
+    .unwrap();
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(modified, 1);
+    assert_eq!(content, "fn main() {  let _x = 3; }");
+    // the file changed since the analysis: it's left untouched
+    let error = apply_replacements(vec![replacement(24, 25, "x", "_x")]).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .ends_with("changed since the analysis, run the job again")
+    );
+    let content = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(content, "fn main() {  let _x = 3; }");
+}
@@ -1,7 +1,11 @@
 use {
     lazy_regex::*,
     std::{
-        path::PathBuf,
+        fmt::Write as _,
+        path::{
+            Path,
+            PathBuf,
+        },
         str::FromStr,
     },
 };
@@ -29,3 +33,70 @@ impl FromStr for Location {
         })
     }
 }
+
+/// Build the `file://` URI of an absolute path
+/// (eg `file:///C:/dir/main.rs` for `C:\dir\main.rs` on Windows)
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if cfg!(windows) {
+        windows_path_to_uri(&path)
+    } else {
+        format!("file://{}", encode_uri_path(&path))
+    }
+}
+
+fn windows_path_to_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    // verbatim paths, as returned by `canonicalize`
+    let path = if let Some(unc) = path.strip_prefix("//?/UNC/") {
+        format!("//{unc}")
+    } else {
+        path.strip_prefix("//?/").unwrap_or(&path).to_string()
+    };
+    if let Some(unc) = path.strip_prefix("//") {
+        // the server of an UNC path is the host of the URI
+        return format!("file://{}", encode_uri_path(unc));
+    }
+    match regex_captures!(r"^([a-zA-Z]):(/.*)?$", &path) {
+        Some((_, drive, rest)) => format!("file:///{drive}:{}", encode_uri_path(rest)),
+        None => format!("file://{}", encode_uri_path(&path)),
+    }
+}
+
+/// Percent-encode all chars of the path but the unreserved ones and `/`
+fn encode_uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            let _ = write!(encoded, "%{b:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_to_uri() {
+    assert_eq!(
+        path_to_uri(Path::new("/home/me/my crate/src/main.rs")),
+        "file:///home/me/my%20crate/src/main.rs",
+    );
+}
+
+#[test]
+fn test_windows_path_to_uri() {
+    assert_eq!(
+        windows_path_to_uri(r"C:\Users\me\my crate\src\main.rs"),
+        "file:///C:/Users/me/my%20crate/src/main.rs",
+    );
+    assert_eq!(
+        windows_path_to_uri(r"\\?\D:\work\lib.rs"),
+        "file:///D:/work/lib.rs",
+    );
+    assert_eq!(
+        windows_path_to_uri(r"\\server\share\lib.rs"),
+        "file://server/share/lib.rs",
+    );
+}
@@ -1,25 +1,33 @@
+mod baseline;
 mod command_output;
 mod command_result;
 mod failure;
 mod filter;
+mod item_diagnostic;
 mod items;
 mod line;
 mod location;
 mod report;
 mod report_maker;
+mod run_diff;
+mod source_snippet;
 mod wrapped_command_output;
 mod wrapped_report;
 
 pub use {
+    baseline::*,
     command_output::*,
     command_result::*,
     failure::*,
     filter::*,
+    item_diagnostic::*,
     items::*,
     line::*,
     location::*,
     report::*,
     report_maker::*,
+    run_diff::*,
+    source_snippet::*,
     wrapped_command_output::*,
     wrapped_report::*,
 };
@@ -2,7 +2,10 @@ use {
     crate::*,
     anyhow::Result,
     lazy_regex::*,
-    rustc_hash::FxHashSet,
+    rustc_hash::{
+        FxHashMap,
+        FxHashSet,
+    },
     serde::{
         Deserialize,
         Serialize,
@@ -31,6 +34,18 @@ pub struct Report {
     pub has_passed_tests: bool,
     pub dismissed_items: usize,
     pub dismissed_lines: Vec<Line>,
+    /// the structured diagnostics of the items, by item index, when
+    /// the analyzer provides them
+    #[serde(default)]
+    pub item_diagnostics: FxHashMap<usize, ItemDiagnostic>,
+    /// the fingerprints of the items hidden because they're in the
+    /// baseline of the job
+    #[serde(default)]
+    pub baselined: Vec<ItemFingerprint>,
+    /// the names of the tests seen passing, when the analyzer
+    /// recognizes them
+    #[serde(default)]
+    pub passed_tests: Vec<String>,
 }
 
 impl Report {
@@ -47,6 +62,9 @@ impl Report {
             stats,
             dismissed_items: 0,
             dismissed_lines: Vec::new(),
+            item_diagnostics: FxHashMap::default(),
+            baselined: Vec::new(),
+            passed_tests: Vec::new(),
         }
     }
 
@@ -158,6 +176,62 @@ impl Report {
         None
     }
 
+    /// Return the fingerprint of every item, built from its diagnostic
+    /// type, the file of its location, and its title.
+    ///
+    /// The line and column aren't part of the fingerprint so that an
+    /// item is recognized even when lines are added before it.
+    pub fn item_fingerprints(&self) -> Vec<(usize, ItemFingerprint)> {
+        Item::items_of(&self.lines)
+            .into_iter()
+            .filter_map(|item| {
+                let title = item
+                    .lines()
+                    .iter()
+                    .find(|line| matches!(line.line_type, LineType::Title(_)))?;
+                let fingerprint = format!(
+                    "{}|{}|{}",
+                    item.diag_type().unwrap_or_default(),
+                    item.location()
+                        .and_then(|location| location.parse::<Location>().ok())
+                        .map(|location| location.path)
+                        .unwrap_or_default()
+                        .display(),
+                    title.content.to_raw(),
+                );
+                Some((item.item_idx(), fingerprint))
+            })
+            .collect()
+    }
+
+    /// Return the code of the diagnostic of the item, eg `E0502`
+    /// or `clippy::needless_return`, if it can be found
+    pub fn item_code(
+        &self,
+        item_idx: usize,
+    ) -> Option<String> {
+        if let Some(code) = self
+            .item_diagnostics
+            .get(&item_idx)
+            .and_then(|diagnostic| diagnostic.code.clone())
+        {
+            return Some(code);
+        }
+        for line in self.lines.iter().filter(|line| line.item_idx == item_idx) {
+            let raw = line.content.to_raw();
+            if let Some((_, code)) = regex_captures!(r"^\w+\[(E\d{4})\]", &raw) {
+                return Some(code.to_string());
+            }
+            if let Some((_, lint)) = regex_captures!(r"#\[\w+\((clippy::\w+)\)\]", &raw) {
+                return Some(lint.to_string());
+            }
+            if let Some((_, lint)) = regex_captures!(r"rust-clippy/[^#\s]+#(\w+)", &raw) {
+                return Some(format!("clippy::{lint}"));
+            }
+        }
+        None
+    }
+
     /// Extract all the diagnostic context, that is all the normal lines
     /// which have the same item index as the given line.
     /// Those lines are taken without style and joined with an escaped newline.
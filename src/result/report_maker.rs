@@ -1,7 +1,8 @@
 use {
     crate::*,
     anyhow::*,
-    std::process::ExitStatus,
+    std::path::PathBuf,
+    termimad::crossbeam::channel::Receiver,
 };
 
 /// Dedicated to a mission, the report maker receives the command
@@ -9,16 +10,23 @@ use {
 pub struct ReportMaker {
     ignored_lines_patterns: Option<Vec<LinePattern>>,
     analyzer: Box<dyn Analyzer>,
+    baseline_path: Option<PathBuf>,
+    source_snippets: Option<SourceSnippets>,
 }
 
 impl ReportMaker {
     pub fn new(mission: &Mission) -> Self {
         let ignored_lines_patterns = mission.ignored_lines_patterns().cloned();
         let analyzer_ref = mission.analyzer();
-        let analyzer = analyzer_ref.create_analyzer();
+        let analyzer = analyzer_ref.create_analyzer(mission.settings);
+        let source_snippets = (mission.settings.source_snippets
+            && analyzer_ref.lacks_source_snippets())
+        .then(|| SourceSnippets::new(mission, analyzer_ref.has_byte_columns()));
         Self {
             ignored_lines_patterns,
             analyzer,
+            baseline_path: mission.baseline_path(),
+            source_snippets,
         }
     }
 
@@ -44,17 +52,26 @@ impl ReportMaker {
         self.analyzer.receive_line(cmd_line, command_output);
     }
 
-    pub fn build_report(&mut self) -> Result<Report> {
-        self.analyzer.build_report()
+    /// Tell the analyzer the output ended, and get a receiver signaling
+    /// when the report is ready, if it's not already
+    pub fn end_of_output(&mut self) -> Option<Receiver<()>> {
+        self.analyzer.end_of_output()
     }
 
-    pub fn build_result(
-        &mut self,
-        output: CommandOutput,
-        exit_status: ExitStatus,
-    ) -> Result<CommandResult> {
-        let report = self.analyzer.build_report()?;
-        let result = CommandResult::build(output, exit_status, report)?;
-        Ok(result)
+    pub fn build_report(&mut self) -> Result<Report> {
+        let mut report = self.analyzer.build_report()?;
+        if let Some(path) = &self.baseline_path {
+            // the baseline is read at every run, as it may have been recorded again.
+            // An invalid baseline file is ignored so that it doesn't prevent the report
+            let baseline = Baseline::read(path).unwrap_or_else(|e| {
+                warn!("ignoring baseline: {e:#}");
+                Baseline::default()
+            });
+            baseline.remove_baselined_items(&mut report);
+        }
+        if let Some(source_snippets) = &self.source_snippets {
+            source_snippets.insert_in(&mut report);
+        }
+        Ok(report)
     }
 }
@@ -0,0 +1,82 @@
+use {
+    crate::*,
+    rustc_hash::{
+        FxHashMap,
+        FxHashSet,
+    },
+};
+
+/// The identity of an item, stable from one run to the next
+/// as long as the item isn't modified
+pub type ItemFingerprint = String;
+
+/// The difference between a report and the one of the previous run
+/// of the same job
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunDiff {
+    /// Indexes of the items which weren't in the previous report
+    pub new_items: FxHashSet<usize>,
+    /// Number of items of the previous report which aren't there anymore
+    pub fixed_count: usize,
+}
+
+impl RunDiff {
+    /// Compare the report with the fingerprints of the items of the
+    /// previous one (identical items may appear several times)
+    pub fn new(
+        previous: &[ItemFingerprint],
+        report: &Report,
+    ) -> Self {
+        let mut unmatched: FxHashMap<&str, usize> = FxHashMap::default();
+        for fingerprint in previous {
+            *unmatched.entry(fingerprint).or_default() += 1;
+        }
+        let mut new_items = FxHashSet::default();
+        for (item_idx, fingerprint) in report.item_fingerprints() {
+            match unmatched.get_mut(fingerprint.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                }
+                _ => {
+                    new_items.insert(item_idx);
+                }
+            }
+        }
+        let fixed_count = unmatched.values().sum();
+        Self {
+            new_items,
+            fixed_count,
+        }
+    }
+    pub fn is_new(
+        &self,
+        item_idx: usize,
+    ) -> bool {
+        self.new_items.contains(&item_idx)
+    }
+}
+
+#[test]
+fn test_run_diff() {
+    let report = |titles: &[&str]| {
+        let lines = titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| Line {
+                item_idx: i + 1,
+                line_type: LineType::Title(Kind::Warning),
+                content: TLine::from_raw(title.to_string()),
+            })
+            .collect();
+        Report::new(lines)
+    };
+    let previous: Vec<ItemFingerprint> = report(&["warning: a", "warning: b"])
+        .item_fingerprints()
+        .into_iter()
+        .map(|(_, fingerprint)| fingerprint)
+        .collect();
+    let diff = RunDiff::new(&previous, &report(&["warning: b", "warning: c"]));
+    assert!(!diff.is_new(1));
+    assert!(diff.is_new(2));
+    assert_eq!(diff.fixed_count, 1);
+}
@@ -0,0 +1,229 @@
+use {
+    crate::*,
+    lazy_regex::*,
+    rustc_hash::{
+        FxHashMap,
+        FxHashSet,
+    },
+    std::{
+        fs,
+        path::PathBuf,
+    },
+    unicode_width::UnicodeWidthChar,
+};
+
+/// Number of source lines shown before and after the line of a location
+const SNIPPET_CONTEXT_LINES: usize = 1;
+
+/// What a tab is displayed as, in snippets
+const TAB_SPACES: &str = "    ";
+
+/// Inserts, under the location of the items, the source lines around
+/// this location, for the tools which don't print them
+pub struct SourceSnippets {
+    package_directory: PathBuf,
+    workspace_directory: Option<PathBuf>,
+    /// Whether the columns of the locations count bytes instead of chars
+    byte_columns: bool,
+}
+
+impl SourceSnippets {
+    pub fn new(
+        mission: &Mission,
+        byte_columns: bool,
+    ) -> Self {
+        Self {
+            package_directory: mission.package_directory.clone(),
+            workspace_directory: mission.workspace_directory.clone(),
+            byte_columns,
+        }
+    }
+    /// Insert a snippet after the first location line of every item, unless
+    /// the tool already printed the source line
+    pub fn insert_in(
+        &self,
+        report: &mut Report,
+    ) {
+        let mut title_kinds = FxHashMap::default();
+        // the items which don't need a snippet, or already got one
+        let mut done_items = FxHashSet::default();
+        for item in Item::items_of(&report.lines) {
+            let lines = item.lines();
+            if let Some(LineType::Title(kind)) = lines.first().map(|line| line.line_type) {
+                title_kinds.insert(item.item_idx(), kind);
+            }
+            let location = item
+                .location()
+                .and_then(|location| location.parse::<Location>().ok());
+            if let Some(location) = location {
+                if lines
+                    .iter()
+                    .any(|line| shows_source_line(line, location.line))
+                {
+                    done_items.insert(item.item_idx());
+                }
+            }
+        }
+        let mut files: FxHashMap<PathBuf, Option<Vec<String>>> = FxHashMap::default();
+        let mut lines = Vec::with_capacity(report.lines.len());
+        for line in report.lines.drain(..) {
+            let item_idx = line.item_idx;
+            let location = line
+                .location()
+                .filter(|_| done_items.insert(item_idx))
+                .and_then(|location| location.parse::<Location>().ok());
+            lines.push(line);
+            let Some(location) = location else {
+                continue;
+            };
+            let path = absolute_report_path(
+                location.path.clone(),
+                &self.package_directory,
+                self.workspace_directory.as_deref(),
+            );
+            let source = files
+                .entry(path)
+                .or_insert_with_key(|path| {
+                    fs::read_to_string(path)
+                        .ok()
+                        .map(|content| content.lines().map(String::from).collect())
+                })
+                .as_deref();
+            let Some(source) = source else {
+                continue;
+            };
+            let caret_style = match title_kinds.get(&item_idx) {
+                Some(Kind::Warning) => CSI_BOLD_YELLOW,
+                _ => CSI_BOLD_RED,
+            };
+            for content in snippet(source, &location, self.byte_columns, caret_style) {
+                lines.push(Line {
+                    item_idx,
+                    line_type: LineType::Snippet,
+                    content,
+                });
+            }
+        }
+        report.lines = lines;
+    }
+}
+
+/// Tell whether the line looks like the display of the source line
+/// `line_number` by the tool (eg `  > 12 │ let a = b;`)
+fn shows_source_line(
+    line: &Line,
+    line_number: usize,
+) -> bool {
+    let raw = line.content.to_raw();
+    regex_captures!(r"^\s*>?\s*(\d+)\s*[│|]", &raw)
+        .and_then(|(_, n)| n.parse::<usize>().ok())
+        .is_some_and(|n| n == line_number)
+}
+
+/// Build the lines showing the source around the location, with a caret
+/// under the column, or nothing if the location's line isn't in the source
+fn snippet(
+    source: &[String],
+    location: &Location,
+    byte_columns: bool,
+    caret_style: &str,
+) -> Vec<TLine> {
+    if location.line == 0 || location.line > source.len() {
+        return Vec::new();
+    }
+    let start = location.line.saturating_sub(SNIPPET_CONTEXT_LINES).max(1);
+    let end = (location.line + SNIPPET_CONTEXT_LINES).min(source.len());
+    let gutter_width = end.to_string().len();
+    let gutter =
+        |number: String| TString::new(CSI_BOLD_4BIT_BLUE, format!("{number:>gutter_width$} | "));
+    let mut tlines = Vec::new();
+    for line_number in start..=end {
+        let code = &source[line_number - 1];
+        tlines.push(TLine {
+            strings: vec![
+                gutter(line_number.to_string()),
+                TString::new("", code.replace('\t', TAB_SPACES)),
+            ],
+        });
+        if line_number != location.line {
+            continue;
+        }
+        let Some(column) = location.column.filter(|&c| c > 0) else {
+            continue;
+        };
+        tlines.push(TLine {
+            strings: vec![
+                gutter(String::new()),
+                TString::new("", " ".repeat(caret_offset(code, column, byte_columns))),
+                TString::new(caret_style, "^"),
+            ],
+        });
+    }
+    tlines
+}
+
+/// Compute the width, once tabs are expanded, of the part of the
+/// (unexpanded) code line before the column
+fn caret_offset(
+    code: &str,
+    column: usize,
+    byte_columns: bool,
+) -> usize {
+    let before = if byte_columns {
+        let mut end = (column - 1).min(code.len());
+        while !code.is_char_boundary(end) {
+            end -= 1;
+        }
+        &code[..end]
+    } else {
+        let end = code
+            .char_indices()
+            .nth(column - 1)
+            .map_or(code.len(), |(idx, _)| idx);
+        &code[..end]
+    };
+    before
+        .chars()
+        .map(|c| match c {
+            '\t' => TAB_SPACES.len(),
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+#[test]
+fn test_snippet() {
+    let source: Vec<String> = ["fn main() {", "    let a = 3;", "}"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let location: Location = "src/main.rs:2:9".parse().unwrap();
+    let raw: Vec<String> = snippet(&source, &location, false, CSI_BOLD_YELLOW)
+        .iter()
+        .map(TLine::to_raw)
+        .collect();
+    assert_eq!(
+        raw,
+        vec![
+            "1 | fn main() {",
+            "2 |     let a = 3;",
+            "  |         ^",
+            "3 | }",
+        ]
+    );
+    let location: Location = "src/main.rs:7:1".parse().unwrap();
+    assert!(snippet(&source, &location, false, CSI_BOLD_YELLOW).is_empty());
+}
+
+#[test]
+fn test_caret_offset() {
+    // a tab before the column is expanded
+    assert_eq!(caret_offset("\tlet a = 3;", 6, false), 8);
+    // a tab after the column doesn't count
+    assert_eq!(caret_offset("let a = 3;\t// x", 5, false), 4);
+    // chars and bytes columns of `x` after a 2 bytes char
+    assert_eq!(caret_offset("é := x", 6, false), 5);
+    assert_eq!(caret_offset("é := x", 7, true), 5);
+    // wide chars
+    assert_eq!(caret_offset("\"日本\", x", 6, false), 7);
+}
@@ -1,12 +1,87 @@
-use crate::*;
+use {
+    crate::*,
+    lazy_regex::regex::{
+        self,
+        Regex,
+        RegexBuilder,
+    },
+};
 
+/// How a search pattern is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PatternOptions {
+    /// the pattern is a regular expression
+    pub regex: bool,
+    pub case_insensitive: bool,
+    /// matches must start and end on word boundaries
+    pub whole_word: bool,
+}
+
+#[derive(Clone)]
 pub struct Pattern {
-    pub pattern: String, // might change later
+    pub pattern: String,
+    /// the compiled regex, when the pattern isn't a plain literal
+    regex: Option<Regex>,
+}
+
+impl PatternOptions {
+    pub fn is_literal(self) -> bool {
+        !(self.regex || self.case_insensitive || self.whole_word)
+    }
+    /// the letters of the enabled options, eg "ri" for a case
+    /// insensitive regex
+    pub fn flags(self) -> String {
+        let mut flags = String::new();
+        if self.regex {
+            flags.push('r');
+        }
+        if self.case_insensitive {
+            flags.push('i');
+        }
+        if self.whole_word {
+            flags.push('w');
+        }
+        flags
+    }
 }
 
 impl Pattern {
+    /// Build a pattern, failing if it's not a valid regular expression
+    /// while the `regex` option is set
+    pub fn new(
+        pattern: String,
+        options: PatternOptions,
+    ) -> Result<Self, regex::Error> {
+        let regex = if options.is_literal() {
+            None
+        } else {
+            let mut source = if options.regex {
+                pattern.clone()
+            } else {
+                regex::escape(&pattern)
+            };
+            if options.whole_word {
+                source = format!(r"\b(?:{source})\b");
+            }
+            let regex = RegexBuilder::new(&source)
+                .case_insensitive(options.case_insensitive)
+                .build()?;
+            Some(regex)
+        };
+        Ok(Self { pattern, regex })
+    }
+    /// Build a pattern matching exactly the given string
+    pub fn literal<S: Into<String>>(pattern: S) -> Self {
+        Self {
+            pattern: pattern.into(),
+            regex: None,
+        }
+    }
     // Current limitations:
     // - a match can't span over more than 2 lines. This is probably fine.
+    // - only literal patterns are searched over line wraps.
+    // - a match going over style changes is only highlighted up to the
+    //   first change.
     /// Search for the pattern in the lines, starting at line `start`.
     ///
     /// The `line_idx` of the returned founds is the absolute index of the line
@@ -30,7 +105,7 @@ impl Pattern {
         let mut founds = Vec::new();
         let mut previous_line: Option<&Line> = None;
         for (line_idx, line) in lines.enumerate().skip(start) {
-            if line.is_continuation() {
+            if line.is_continuation() && self.regex.is_none() {
                 if let Some(previous_line) = previous_line {
                     // we check for a match broken by wrapping
                     if !previous_line.content.strings.is_empty() && !line.content.strings.is_empty()
@@ -62,24 +137,29 @@ impl Pattern {
                 }
             }
             previous_line = Some(line);
-            for (string_idx, tstring) in line.content.strings.iter().enumerate() {
-                let mut offset = 0;
-                while offset + len <= tstring.raw.len() {
-                    let haystack = &tstring.raw[offset..];
-                    let Some(pos) = haystack.find(pattern) else {
-                        break;
-                    };
-                    let found = Found {
+            // the pattern is searched in the whole line, so that a match may
+            // go over style changes and anchors apply to the line
+            let raw = line.content.to_raw();
+            let ranges: Vec<(usize, usize)> = match &self.regex {
+                Some(regex) => regex
+                    .find_iter(&raw)
+                    .map(|m| (m.start(), m.end()))
+                    .collect(),
+                None => raw
+                    .match_indices(pattern.as_str())
+                    .map(|(start, m)| (start, start + m.len()))
+                    .collect(),
+            };
+            for (match_start, match_end) in ranges {
+                if match_start == match_end {
+                    continue;
+                }
+                if let Some(trange) = trange_of(&line.content, match_start, match_end) {
+                    founds.push(Found {
                         line_idx,
-                        trange: TRange {
-                            string_idx,
-                            start_byte_in_string: pos + offset,
-                            end_byte_in_string: pos + offset + pattern.len(),
-                        },
+                        trange,
                         continued: None,
-                    };
-                    founds.push(found);
-                    offset += pos + pattern.len();
+                    });
                 }
             }
         }
@@ -87,6 +167,31 @@ impl Pattern {
     }
 }
 
+/// Map a byte range of the raw line to a range in one of its strings.
+///
+/// As a `TRange` can't span over several strings, a range going over
+/// style changes is cut at the end of the string it starts in (so only
+/// this part of the match is highlighted).
+fn trange_of(
+    tline: &TLine,
+    start: usize,
+    end: usize,
+) -> Option<TRange> {
+    let mut string_start = 0;
+    for (string_idx, tstring) in tline.strings.iter().enumerate() {
+        let string_end = string_start + tstring.raw.len();
+        if start < string_end {
+            return Some(TRange {
+                string_idx,
+                start_byte_in_string: start - string_start,
+                end_byte_in_string: end.min(string_end) - string_start,
+            });
+        }
+        string_start = string_end;
+    }
+    None
+}
+
 fn find_cut_pattern(
     pattern: &str,
     a: &str,
@@ -120,9 +225,7 @@ mod tests {
     /// must be found: this is the regression for the `<` vs `<=` off-by-one.
     #[test]
     fn find_match_equal_to_whole_string() {
-        let pattern = Pattern {
-            pattern: "error".to_string(),
-        };
+        let pattern = Pattern::literal("error");
         let lines = [raw_line("error")];
         let founds = pattern.search_lines(&lines, 0);
         assert_eq!(founds.len(), 1);
@@ -136,9 +239,7 @@ mod tests {
     /// be found.
     #[test]
     fn find_adjacent_matches() {
-        let pattern = Pattern {
-            pattern: "ab".to_string(),
-        };
+        let pattern = Pattern::literal("ab");
         let lines = [raw_line("abab")];
         let founds = pattern.search_lines(&lines, 0);
         assert_eq!(founds.len(), 2);
@@ -146,4 +247,79 @@ mod tests {
         assert_eq!(founds[1].trange.start_byte_in_string, 2);
         assert_eq!(founds[1].trange.end_byte_in_string, 4);
     }
+
+    #[test]
+    fn find_with_options() {
+        let lines = [raw_line("error[E0502]: Errors in error_code")];
+        let search = |pattern: &str, regex, case_insensitive, whole_word| {
+            let options = PatternOptions {
+                regex,
+                case_insensitive,
+                whole_word,
+            };
+            Pattern::new(pattern.to_string(), options)
+                .unwrap()
+                .search_lines(&lines, 0)
+                .iter()
+                .map(|found| {
+                    (
+                        found.trange.start_byte_in_string,
+                        found.trange.end_byte_in_string,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("error", false, false, false), vec![(0, 5), (24, 29)]);
+        assert_eq!(
+            search("error", false, true, false),
+            vec![(0, 5), (14, 19), (24, 29)]
+        );
+        assert_eq!(search("error", false, true, true), vec![(0, 5)]);
+        assert_eq!(search(r"E0\d{3}", true, false, false), vec![(6, 11)]);
+        assert_eq!(search("[E0502]", false, false, false), vec![(5, 12)]);
+        assert!(
+            Pattern::new(
+                "(".to_string(),
+                PatternOptions {
+                    regex: true,
+                    ..Default::default()
+                }
+            )
+            .is_err()
+        );
+    }
+
+    /// Matches are searched in the whole line, whatever its styling
+    #[test]
+    fn find_over_style_changes() {
+        let lines = [Line {
+            item_idx: 0,
+            line_type: LineType::Normal,
+            content: TLine::from_tty("\u{1b}[1merror\u{1b}[0m: bad \u{1b}[1mthing\u{1b}[0m"),
+        }];
+        let search = |pattern: &str| {
+            let options = PatternOptions {
+                regex: true,
+                ..Default::default()
+            };
+            Pattern::new(pattern.to_string(), options)
+                .unwrap()
+                .search_lines(&lines, 0)
+                .iter()
+                .map(|found| found.trange)
+                .collect::<Vec<_>>()
+        };
+        let trange = |string_idx, start_byte_in_string, end_byte_in_string| TRange {
+            string_idx,
+            start_byte_in_string,
+            end_byte_in_string,
+        };
+        // a match starting in a string is cut at its end
+        assert_eq!(search("error: bad"), vec![trange(0, 0, 5)]);
+        assert_eq!(search(": bad th"), vec![trange(1, 0, 6)]);
+        // anchors apply to the whole line
+        assert_eq!(search("^bad"), vec![]);
+        assert_eq!(search("thing$"), vec![trange(2, 0, 5)]);
+        assert_eq!(search("^error"), vec![trange(0, 0, 5)]);
+    }
 }
@@ -0,0 +1,192 @@
+use {
+    crate::*,
+    anyhow::{
+        Context as _,
+        Result,
+        bail,
+    },
+    std::{
+        io::{
+            self,
+            BufRead,
+            BufReader,
+            Write,
+        },
+        net::Shutdown,
+        os::unix::net::UnixStream,
+        process,
+        time::{
+            Duration,
+            Instant,
+            SystemTime,
+        },
+    },
+};
+
+/// How long `send_action_and_wait` waits for the action to start a task
+const TASK_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn connect(context: &Context) -> Result<UnixStream> {
+    let path = context.unix_socket_path();
+    UnixStream::connect(&path)
+        .with_context(|| format!("Failed to connect to socket: {}", path.display()))
+}
+
+/// Send an action to the bacon instance listening on the socket,
+/// and fail if it doesn't accept it
+pub fn send_action(
+    context: &Context,
+    action: &str,
+) -> Result<()> {
+    send_request(context, &Request::Action(action.to_string()))
+}
+
+/// Send an action request and fail if it isn't accepted
+fn send_request(
+    context: &Context,
+    request: &Request,
+) -> Result<()> {
+    let mut stream = connect(context)?;
+    let request = serde_json::to_string(request)?;
+    writeln!(stream, "{request}")?;
+    stream.flush()?;
+    stream.shutdown(Shutdown::Write)?;
+    // older bacon versions don't answer, so we don't wait forever
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut answer = String::new();
+    if let Err(e) = BufReader::new(&stream).read_line(&mut answer) {
+        debug!("no answer received: {e}");
+        return Ok(());
+    }
+    match serde_json::from_str::<Response>(answer.trim()) {
+        Ok(Response::Error(e)) => bail!("{e}"),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            debug!("unexpected answer {answer:?}: {e}");
+            Ok(())
+        }
+    }
+}
+
+/// Send an action to the bacon instance listening on the socket, then
+/// wait for the task it triggers to finish, and return the final status.
+///
+/// The action is sent with an id so that only the task it starts is
+/// waited for, not one started by a file change or another client.
+///
+/// Fail if the action doesn't start a task (eg `toggle-summary`)
+/// within `TASK_START_TIMEOUT`.
+pub fn send_action_and_wait(
+    context: &Context,
+    action: &str,
+) -> Result<MissionStatus> {
+    // we subscribe before sending the action, so that no event is missed
+    let mut reader = open_subscription(context)?;
+    let id = action_id();
+    send_request(
+        context,
+        &Request::TrackedAction {
+            action: action.to_string(),
+            id: id.clone(),
+        },
+    )?;
+    let deadline = Instant::now() + TASK_START_TIMEOUT;
+    let mut started = false;
+    // kept across reads, as a timeout may happen in the middle of a line
+    let mut line = Vec::new();
+    loop {
+        if !started {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                bail!(
+                    "No task started within {}s: the {action:?} action doesn't seem to run a job",
+                    TASK_START_TIMEOUT.as_secs(),
+                );
+            }
+            reader.get_ref().set_read_timeout(Some(remaining))?;
+        }
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => bail!("bacon stopped before the end of the task"),
+            Ok(_) if line.ends_with(b"\n") => {}
+            Ok(_) => continue, // the end of the line is still to come
+            Err(e) if is_timeout(&e) => continue, // the deadline is checked on next turn
+            Err(e) => return Err(e.into()),
+        }
+        let event: MissionEvent = serde_json::from_slice(&line)?;
+        line.clear();
+        debug!("received event: {event:?}");
+        match event {
+            MissionEvent::TaskStarted { trigger, .. } if trigger.as_ref() == Some(&id) => {
+                started = true;
+                // the task itself may be as long as it wants
+                reader.get_ref().set_read_timeout(None)?;
+            }
+            MissionEvent::TaskStarted { .. } if started => {
+                bail!("The task was replaced by another one before its end");
+            }
+            MissionEvent::TaskFinished(status) if started => {
+                return Ok(status);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Build an id which can't be given by another client at the same time
+fn action_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    format!("{}-{nanos}", process::id())
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Ask for a subscription and check it's accepted, returning the
+/// reader on which the events will come
+fn open_subscription(context: &Context) -> Result<BufReader<UnixStream>> {
+    let mut stream = connect(context)?;
+    let request = serde_json::to_string(&Request::Subscribe)?;
+    writeln!(stream, "{request}")?;
+    stream.flush()?;
+    let mut reader = BufReader::new(stream);
+    let mut answer = String::new();
+    reader.read_line(&mut answer)?;
+    if !serde_json::from_str::<Response>(answer.trim()).is_ok_and(|r| r == Response::Ack) {
+        bail!("This bacon instance doesn't support subscriptions");
+    }
+    Ok(reader)
+}
+
+/// Subscribe to the events of the bacon instance listening on the socket
+pub fn subscribe(context: &Context) -> Result<impl Iterator<Item = Result<MissionEvent>> + use<>> {
+    let reader = open_subscription(context)?;
+    Ok(reader.lines().map(|line| {
+        let event = serde_json::from_str(&line?)?;
+        debug!("received event: {event:?}");
+        Ok(event)
+    }))
+}
+
+/// Ask the bacon instance listening on the socket about its mission
+pub fn query(
+    context: &Context,
+    query: Query,
+) -> Result<Response> {
+    let mut stream = connect(context)?;
+    let request = serde_json::to_string(&Request::Query(query))?;
+    writeln!(stream, "{request}")?;
+    stream.flush()?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut answer = String::new();
+    BufReader::new(&stream).read_line(&mut answer)?;
+    match serde_json::from_str(answer.trim())? {
+        Response::Error(e) => bail!("{e}"),
+        response => Ok(response),
+    }
+}
@@ -0,0 +1,123 @@
+use {
+    crate::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::sync::{
+        Arc,
+        Mutex,
+    },
+    termimad::crossbeam::channel::{
+        Receiver,
+        Sender,
+        unbounded,
+    },
+};
+
+/// Something which happened in bacon, pushed as a JSON line
+/// to the clients which subscribed on the socket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MissionEvent {
+    /// A mission started on a job, either at launch or
+    /// because the user switched to another job
+    JobSwitched { job: ConcreteJobRef },
+    /// The command of the job was (re)launched
+    TaskStarted {
+        job: ConcreteJobRef,
+        /// The id of the tracked action which started the task, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trigger: Option<String>,
+    },
+    /// The running command wrote a line (TTY styling removed)
+    LineReceived {
+        content: String,
+        origin: CommandStream,
+    },
+    /// The command ended and its result was analyzed
+    TaskFinished(MissionStatus),
+    /// The configuration files were read again
+    ConfigReloaded,
+    /// Some items were dismissed or undismissed
+    DismissalChanged { dismissals: Vec<Dismissal> },
+}
+
+/// The senders of the clients which subscribed to mission events.
+///
+/// Clones share the same set of subscribers.
+#[derive(Debug, Clone, Default)]
+pub struct Subscribers {
+    senders: Arc<Mutex<Vec<Sender<MissionEvent>>>>,
+}
+
+impl Subscribers {
+    /// Register a new subscriber, which will receive all
+    /// events sent from now on
+    pub fn subscribe(&self) -> Receiver<MissionEvent> {
+        let (tx, rx) = unbounded();
+        self.senders.lock().unwrap().push(tx);
+        rx
+    }
+    pub fn is_empty(&self) -> bool {
+        self.senders.lock().unwrap().is_empty()
+    }
+    /// Send the event to all subscribers, forgetting the ones
+    /// which are gone
+    pub fn send(
+        &self,
+        event: MissionEvent,
+    ) {
+        let mut senders = self.senders.lock().unwrap();
+        senders.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+impl MissionState<'_, '_> {
+    /// Send an event to the clients which subscribed on the socket
+    pub fn notify(
+        &self,
+        event: MissionEvent,
+    ) {
+        self.app_state.subscribers.send(event);
+    }
+    pub fn notify_task_started(&mut self) {
+        let trigger = self.app_state.task_trigger.take();
+        self.notify(MissionEvent::TaskStarted {
+            job: self.mission.concrete_job_ref.clone(),
+            trigger,
+        });
+    }
+    pub fn notify_dismissals(&self) {
+        self.notify(MissionEvent::DismissalChanged {
+            dismissals: self.app_state.filter.dismissals().to_vec(),
+        });
+    }
+}
+
+impl MissionEvent {
+    pub fn to_line(&self) -> String {
+        match serde_json::to_string(self) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("failed to serialize event: {e}");
+                r#"{"event":"error"}"#.to_string()
+            }
+        }
+    }
+}
+
+#[test]
+fn test_event_serialization() {
+    assert_eq!(
+        MissionEvent::ConfigReloaded.to_line(),
+        r#"{"event":"config_reloaded"}"#,
+    );
+    assert_eq!(
+        MissionEvent::DismissalChanged {
+            dismissals: vec![Dismissal::DiagType("dead_code".to_string())],
+        }
+        .to_line(),
+        r#"{"event":"dismissal_changed","dismissals":[{"diag_type":"dead_code"}]}"#,
+    );
+}
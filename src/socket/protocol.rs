@@ -0,0 +1,157 @@
+use {
+    crate::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    termimad::crossbeam::channel::Sender,
+};
+
+/// A request sent by a client on bacon's socket, as one JSON line.
+///
+/// Lines which aren't JSON objects or strings are read as actions,
+/// for compatibility with the older, action only, protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Request {
+    /// Execute an action, written as in keybindings (eg `"rerun"`)
+    Action(String),
+    /// Execute an action and give the id to the `task_started` event
+    /// of the task it starts, if any
+    TrackedAction { action: String, id: String },
+    /// Get some information about the current mission
+    Query(Query),
+    /// Receive mission events, one JSON line per event, until
+    /// the connection is closed
+    Subscribe,
+}
+
+/// What a client may ask about the running mission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Query {
+    /// The job, computing and auto-refresh states, and the stats
+    Status,
+    /// The complete current report, if any
+    Report,
+}
+
+/// The answer to a request, sent as one JSON line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    /// The action was understood and handed to the mission
+    Ack,
+    /// The request couldn't be parsed or executed
+    Error(String),
+    Status(MissionStatus),
+    Report(Option<Report>),
+}
+
+/// A summary of the state of the current mission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissionStatus {
+    pub job: ConcreteJobRef,
+    pub computing: bool,
+    pub auto_refresh: AutoRefresh,
+    pub changes_since_last_job_start: usize,
+    /// Stats of the last report, if there's one
+    pub stats: Option<Stats>,
+    pub success: bool,
+    pub failure: bool,
+    /// The badges bacon displays on top (project, job, counts)
+    pub badges: Vec<TString>,
+}
+
+/// An action received on the socket, for the mission to execute
+pub struct ReceivedAction {
+    pub action: Action,
+    /// The id given by the client to recognize the task it starts
+    pub trigger: Option<String>,
+}
+
+/// A query received on the socket, waiting for the mission
+/// to answer on the provided channel
+pub struct PendingQuery {
+    pub query: Query,
+    pub response_tx: Sender<Response>,
+}
+
+impl Request {
+    /// Parse a line received on the socket
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        if line.starts_with('{') || line.starts_with('"') {
+            serde_json::from_str(line).map_err(|e| format!("Invalid request: {e}"))
+        } else {
+            Ok(Self::Action(line.to_string()))
+        }
+    }
+}
+
+impl Response {
+    pub fn to_line(&self) -> String {
+        match serde_json::to_string(self) {
+            Ok(json) => json,
+            Err(e) => {
+                // can't really happen, but let's not leave the client hanging
+                error!("failed to serialize response: {e}");
+                r#"{"error":"failed to serialize response"}"#.to_string()
+            }
+        }
+    }
+}
+
+impl MissionState<'_, '_> {
+    pub fn status(&self) -> MissionStatus {
+        MissionStatus {
+            job: self.mission.concrete_job_ref.clone(),
+            computing: self.is_computing(),
+            auto_refresh: self.auto_refresh,
+            changes_since_last_job_start: self.changes_since_last_job_start,
+            stats: self.cmd_result.report().map(|report| report.stats.clone()),
+            success: self.is_success(),
+            failure: self.is_failure(),
+            badges: self.job_badges(),
+        }
+    }
+    /// Build the response to a query received on the socket
+    pub fn answer(
+        &self,
+        query: Query,
+    ) -> Response {
+        match query {
+            Query::Status => Response::Status(self.status()),
+            Query::Report => Response::Report(self.cmd_result.report().cloned()),
+        }
+    }
+}
+
+#[test]
+fn test_request_parsing() {
+    assert_eq!(
+        Request::from_line("scroll-lines(-2)"),
+        Ok(Request::Action("scroll-lines(-2)".to_string())),
+    );
+    assert_eq!(
+        Request::from_line(r#"{"action":"job:clippy"}"#),
+        Ok(Request::Action("job:clippy".to_string())),
+    );
+    assert_eq!(
+        Request::from_line(r#"{"query":"status"}"#),
+        Ok(Request::Query(Query::Status)),
+    );
+    assert_eq!(
+        Request::from_line(r#"{"tracked_action":{"action":"rerun","id":"42"}}"#),
+        Ok(Request::TrackedAction {
+            action: "rerun".to_string(),
+            id: "42".to_string(),
+        }),
+    );
+    assert_eq!(Request::from_line(r#""subscribe""#), Ok(Request::Subscribe));
+    assert!(Request::from_line(r#"{"query":"everything"}"#).is_err());
+    assert_eq!(Response::Ack.to_line(), r#""ack""#);
+    assert_eq!(
+        Response::Error("nope".to_string()).to_line(),
+        r#"{"error":"nope"}"#,
+    );
+}
@@ -9,12 +9,19 @@ use {
         io::{
             BufRead,
             BufReader,
+            Write,
+        },
+        os::unix::net::{
+            UnixListener,
+            UnixStream,
         },
-        os::unix::net::UnixListener,
         path::PathBuf,
         thread,
     },
-    termimad::crossbeam::channel::Sender,
+    termimad::crossbeam::channel::{
+        self,
+        Sender,
+    },
 };
 
 pub struct Server {
@@ -24,7 +31,9 @@ pub struct Server {
 impl Server {
     pub fn new(
         context: &Context,
-        tx: Sender<Action>,
+        action_tx: Sender<ReceivedAction>,
+        query_tx: Sender<PendingQuery>,
+        subscribers: Subscribers,
     ) -> Result<Self> {
         let path = context.unix_socket_path();
         if fs::metadata(&path).is_ok() {
@@ -39,32 +48,12 @@ impl Server {
                     warn!("error while accepting connection");
                     continue;
                 };
-                let tx = tx.clone();
+                let action_tx = action_tx.clone();
+                let query_tx = query_tx.clone();
+                let subscribers = subscribers.clone();
                 thread::spawn(move || {
                     debug!("new connection");
-                    let mut br = BufReader::new(&stream);
-                    let mut line = String::new();
-                    while br.read_line(&mut line).is_ok() {
-                        while line.ends_with('\n') || line.ends_with('\r') {
-                            line.pop();
-                        }
-                        debug!("line => {:?}", line);
-                        if line.is_empty() {
-                            debug!("empty line, closing connection");
-                            break;
-                        }
-                        match line.parse() {
-                            Ok(action) => {
-                                if tx.send(action).is_err() {
-                                    error!("failed to send action");
-                                }
-                            }
-                            Err(e) => {
-                                warn!("failed to parse action: {e}");
-                            }
-                        }
-                        line.clear();
-                    }
+                    handle_connection(&stream, &action_tx, &query_tx, &subscribers);
                     debug!("closed connection");
                 });
             }
@@ -73,6 +62,105 @@ impl Server {
     }
 }
 
+/// Read the requests of a client, one per line, and answer each of
+/// them with a response line.
+///
+/// After a subscription, the connection is only used to push events.
+fn handle_connection(
+    stream: &UnixStream,
+    action_tx: &Sender<ReceivedAction>,
+    query_tx: &Sender<PendingQuery>,
+    subscribers: &Subscribers,
+) {
+    let mut br = BufReader::new(stream);
+    let mut writer = stream;
+    let mut line = String::new();
+    while br.read_line(&mut line).is_ok() {
+        while line.ends_with('\n') || line.ends_with('\r') {
+            line.pop();
+        }
+        debug!("line => {:?}", line);
+        if line.is_empty() {
+            debug!("empty line, closing connection");
+            break;
+        }
+        let response = match Request::from_line(&line) {
+            Ok(Request::Subscribe) => {
+                stream_events(writer, subscribers);
+                break;
+            }
+            Ok(request) => handle_request(request, action_tx, query_tx),
+            Err(e) => Response::Error(e),
+        };
+        if let Response::Error(e) = &response {
+            warn!("error in socket request: {e}");
+        }
+        let answer = response.to_line();
+        if let Err(e) = writeln!(writer, "{answer}").and_then(|()| writer.flush()) {
+            debug!("failed to answer: {e}"); // client probably left
+            break;
+        }
+        line.clear();
+    }
+}
+
+/// Acknowledge the subscription then write the mission events
+/// until the client leaves
+fn stream_events(
+    mut writer: &UnixStream,
+    subscribers: &Subscribers,
+) {
+    let event_rx = subscribers.subscribe();
+    let ack = Response::Ack.to_line();
+    if writeln!(writer, "{ack}")
+        .and_then(|()| writer.flush())
+        .is_err()
+    {
+        return;
+    }
+    for event in event_rx {
+        let line = event.to_line();
+        if let Err(e) = writeln!(writer, "{line}").and_then(|()| writer.flush()) {
+            debug!("subscriber left: {e}");
+            break;
+        }
+    }
+}
+
+fn handle_request(
+    request: Request,
+    action_tx: &Sender<ReceivedAction>,
+    query_tx: &Sender<PendingQuery>,
+) -> Response {
+    let (action, trigger) = match request {
+        Request::Action(action) => (action, None),
+        Request::TrackedAction { action, id } => (action, Some(id)),
+        Request::Subscribe => {
+            return Response::Error("subscriptions aren't queries".to_string());
+        }
+        Request::Query(query) => {
+            let (response_tx, response_rx) = channel::bounded(1);
+            if query_tx.send(PendingQuery { query, response_tx }).is_err() {
+                error!("failed to send query");
+                return Response::Error("bacon isn't running any mission".to_string());
+            }
+            return response_rx
+                .recv()
+                .unwrap_or_else(|_| Response::Error("mission ended before answering".to_string()));
+        }
+    };
+    match action.parse::<Action>() {
+        Ok(action) => {
+            if action_tx.send(ReceivedAction { action, trigger }).is_err() {
+                error!("failed to send action");
+                return Response::Error("bacon isn't running any mission".to_string());
+            }
+            Response::Ack
+        }
+        Err(e) => Response::Error(e.to_string()),
+    }
+}
+
 impl Drop for Server {
     fn drop(&mut self) {
         debug!("removing socket file");
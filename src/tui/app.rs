@@ -1,6 +1,9 @@
 use {
     crate::*,
-    anyhow::Result,
+    anyhow::{
+        Result,
+        bail,
+    },
     crokey::*,
     std::{
         io::Write,
@@ -12,18 +15,26 @@ use {
         Ticker,
         crossbeam::channel::{
             Receiver,
+            Sender,
+            never,
             select,
         },
-        crossterm::event::Event,
-    },
-};
-
-#[cfg(windows)]
-use {
-    crokey::key,
-    termimad::crossterm::event::{
-        MouseEvent,
-        MouseEventKind,
+        crossterm::{
+            QueueableCommand,
+            cursor,
+            event::{
+                DisableMouseCapture,
+                EnableMouseCapture,
+                Event,
+            },
+            terminal::{
+                self,
+                Clear,
+                ClearType,
+                EnterAlternateScreen,
+                LeaveAlternateScreen,
+            },
+        },
     },
 };
 
@@ -49,6 +60,7 @@ pub fn run(
 ) -> Result<()> {
     let mut app_state = AppState {
         headless,
+        filter: Filter::load(context.dismissals_path(), &settings.dismissals),
         ..Default::default()
     };
     let event_source = if headless {
@@ -70,9 +82,19 @@ pub fn run(
     };
     #[allow(unused_variables)]
     let (action_tx, action_rx) = termimad::crossbeam::channel::unbounded();
+    #[allow(unused_variables)]
+    let (query_tx, query_rx) = termimad::crossbeam::channel::unbounded();
+    // outcomes of the commands launched by shell actions, which may
+    // arrive after the user switched to another job
+    let (shell_tx, shell_rx) = termimad::crossbeam::channel::unbounded();
     #[cfg(unix)]
     let _server = if settings.listen {
-        Some(Server::new(context, action_tx.clone())?)
+        Some(Server::new(
+            context,
+            action_tx.clone(),
+            query_tx.clone(),
+            app_state.subscribers.clone(),
+        )?)
     } else {
         None
     };
@@ -80,26 +102,59 @@ pub fn run(
     let mut next_job = JobRef::Initial;
     let mut message = None;
     loop {
-        let Some((concrete_job_ref, job)) = job_stack.pick_job(&next_job, &settings)? else {
-            break;
+        // the concurrent jobs keep running while the user switches
+        // jobs, until the config changes
+        let mut dashboard = Dashboard::new(context, &settings)?;
+        let do_after = loop {
+            let Some((concrete_job_ref, job)) = job_stack.pick_job(&next_job, &settings)? else {
+                break DoAfterMission::Quit;
+            };
+            let prerequisites = job_prerequisites(&concrete_job_ref, &job, &settings)?;
+            let mut mission = context.mission(concrete_job_ref, &job, &settings)?;
+            for (prerequisite_ref, prerequisite_job) in prerequisites {
+                let prerequisite =
+                    context.mission(prerequisite_ref, &prerequisite_job, &settings)?;
+                mission.prerequisites.push(prerequisite);
+            }
+            match app::run_mission(
+                w,
+                &mut app_state,
+                mission,
+                &mut dashboard,
+                event_source.as_ref(),
+                &action_rx,
+                &query_rx,
+                &shell_tx,
+                &shell_rx,
+                message.take(),
+            )? {
+                DoAfterMission::NextJob(job_ref) => {
+                    next_job = job_ref;
+                }
+                do_after => {
+                    break do_after;
+                }
+            }
         };
-        let mission = context.mission(concrete_job_ref, &job, &settings)?;
-        let do_after = app::run_mission(
-            w,
-            &mut app_state,
-            mission,
-            event_source.as_ref(),
-            &action_rx,
-            message.take(),
-        )?;
+        drop(dashboard);
         match do_after {
             DoAfterMission::NextJob(job_ref) => {
                 next_job = job_ref;
             }
             DoAfterMission::ReloadConfig => match Settings::read(args, context) {
                 Ok(new_settings) => {
+                    if !headless && new_settings.mouse_capture != settings.mouse_capture {
+                        if new_settings.mouse_capture {
+                            w.queue(EnableMouseCapture)?;
+                        } else {
+                            w.queue(DisableMouseCapture)?;
+                        }
+                        w.flush()?;
+                    }
                     settings = new_settings;
+                    app_state.filter.set_configured(&settings.dismissals);
                     message = Some(Message::short("Config reloaded"));
+                    app_state.subscribers.send(MissionEvent::ConfigReloaded);
                 }
                 Err(e) => {
                     message = Some(Message::short(format!("Invalid config: {e}")));
@@ -114,12 +169,17 @@ pub fn run(
 }
 
 /// Run the mission and return what to do afterwards
+#[allow(clippy::too_many_arguments)]
 fn run_mission(
     w: &mut W,
     app_state: &mut AppState,
     mission: Mission,
+    dashboard: &mut Dashboard,
     event_source: Option<&EventSource>,
-    action_rx: &Receiver<Action>,
+    action_rx: &Receiver<ReceivedAction>,
+    query_rx: &Receiver<PendingQuery>,
+    shell_tx: &Sender<ShellOutcome>,
+    shell_rx: &Receiver<ShellOutcome>,
     message: Option<Message>,
 ) -> Result<DoAfterMission> {
     let headless = app_state.headless;
@@ -131,23 +191,58 @@ fn run_mission(
 
     // build the watcher detecting and transmitting mission file changes
     let ignorer = time!(Info, mission.ignorer());
-    let mission_watcher = Watcher::new(&mission.paths_to_watch, ignorer)?;
+    let mission_watcher = Watcher::new(&mission.all_paths_to_watch(), ignorer)?;
 
     // create the watcher for config file changes
     let config_watcher = Watcher::new(&mission.settings.config_files, IgnorerSet::default())?;
 
     // create the executor, mission, and state
-    let mut executor = MissionExecutor::new(&mission)?;
+    // one executor per stage: the prerequisites, then the mission's job
+    let mut executors = mission
+        .prerequisites
+        .iter()
+        .chain(std::iter::once(&mission))
+        .map(MissionExecutor::new)
+        .collect::<Result<Vec<_>>>()?;
     let on_change_strategy = mission.job.on_change_strategy();
+    // when the job is one of the dashboard, its last result may still be valid
+    let up_to_date_result = dashboard.focus(&mission.concrete_job_ref)?;
     let mut mission_state = MissionState::new(app_state, mission)?;
     if let Some(message) = message {
         mission_state.messages.push(message);
     }
-    mission_state.computation_starts();
+    mission_state.notify(MissionEvent::JobSwitched {
+        job: mission_state.mission.concrete_job_ref.clone(),
+    });
+    let mut task_executor = if let Some(result) = up_to_date_result {
+        info!("reusing the result of the dashboard");
+        mission_state.set_result(result);
+        // for subscribers, it's as if an instant task ran
+        mission_state.notify_task_started();
+        mission_state.notify(MissionEvent::TaskFinished(mission_state.status()));
+        if headless {
+            for badge in mission_state.job_badges() {
+                badge.draw(w)?;
+            }
+            writeln!(w)?;
+            w.flush()?;
+        }
+        None
+    } else {
+        // while the job runs, we show its previous report, if any
+        mission_state.show_cached_report();
+        mission_state.computation_starts();
+        mission_state.notify_task_started();
+        Some(mission_state.start_task(&mut executors)?) // first computation
+    };
     if !headless {
+        mission_state.dashboard_badges = dashboard.badges(
+            mission_state.is_computing(),
+            &mission_state.mission.job.skin,
+        );
         mission_state.draw(w)?;
     }
-    let mut task_executor = executor.start(mission_state.new_task())?; // first computation
+    let no_lines = never();
 
     // A very low frequency tick generator, to ensure "config loaded" message doesn't stick
     // too long on the screen
@@ -170,21 +265,25 @@ fn run_mission(
         // While it's a vec, action execution will stop at the first one quitting the
         // mission or requesting a task execution, and the rest of the vec will be dropped.
         let mut actions: Vec<Action> = Vec::new();
+        // When a user event is received, the event source waits for
+        // the actions to be done before reading the terminal again, so
+        // that a program launched by an action can have the terminal
+        let mut user_event_received = false;
         select! {
             recv(ticker.tick_receiver) -> _ => {
                 // just redraw
             }
             recv(mission_watcher.receiver) -> _ => {
                 debug!("watch event received");
-                if task_executor.is_in_grace_period() {
+                if task_executor.as_mut().is_some_and(TaskExecutor::is_in_grace_period) {
                     debug!("ignoring notify event in grace period");
                     continue;
                 }
                 mission_state.receive_watch_event();
-                if mission_state.auto_refresh.is_enabled() {
-                    if !mission_state.is_computing() || on_change_strategy == OnChangeStrategy::KillThenRestart {
-                        actions.push(Action::ReRun);
-                    }
+                if mission_state.auto_refresh.is_enabled()
+                    && (!mission_state.is_computing() || on_change_strategy == OnChangeStrategy::KillThenRestart)
+                {
+                    actions.push(Action::ReRun);
                 }
             }
             recv(config_watcher.receiver) -> _ => {
@@ -194,7 +293,7 @@ fn run_mission(
                     actions.push(Action::ReloadConfig);
                 }
             }
-            recv(task_executor.line_receiver) -> info => {
+            recv(task_executor.as_ref().map_or(&no_lines, |t| &t.line_receiver)) -> info => {
                 if let Ok(info) = info {
                     match info {
                         CommandExecInfo::Line(line) => {
@@ -204,34 +303,56 @@ fn run_mission(
                                     CommandStream::StdErr => eprint!("{}", line.content),
                                 }
                             }
-                            let line = line.into();
+                            let line: CommandOutputLine = line.into();
+                            if !mission_state.app_state.subscribers.is_empty() {
+                                mission_state.notify(MissionEvent::LineReceived {
+                                    content: line.content.to_raw(),
+                                    origin: line.origin,
+                                });
+                            }
                             mission_state.add_line(line);
                         }
                         CommandExecInfo::End { status } => {
+                            if let Some(ready) = mission_state.end_of_output() {
+                                // the analyzer isn't done, the end will come again when it is
+                                if let Some(task_executor) = &task_executor {
+                                    task_executor.end_when_ready(ready, status);
+                                }
+                                continue;
+                            }
                             // computation finished
                             info!("execution finished with status: {status:?}");
                             mission_state.finish_task(status)?;
-                            if headless {
-                                for badge in mission_state.job_badges() {
-                                    badge.draw(w)?;
+                            if let Some(next_task_executor) = mission_state.start_next_stage(&mut executors)? {
+                                // a prerequisite passed, the pipeline goes on
+                                task_executor = Some(next_task_executor);
+                            } else {
+                                if !mission_state.is_prerequisite_stage() {
+                                    dashboard.set_focused_result(&mission_state.cmd_result);
                                 }
-                                writeln!(w)?;
-                                w.flush()?;
-                            }
-                            if mission_state.is_success() {
-                                if let Some(action) = &mission_state.mission.job.on_success {
-                                    actions.push(action.clone());
+                                mission_state.notify(MissionEvent::TaskFinished(mission_state.status()));
+                                if headless {
+                                    for badge in mission_state.job_badges() {
+                                        badge.draw(w)?;
+                                    }
+                                    writeln!(w)?;
+                                    w.flush()?;
                                 }
-                            }
-                            if mission_state.is_failure() {
-                                if let Some(action) = &mission_state.mission.job.on_failure {
-                                    actions.push(action.clone());
+                                if mission_state.is_success() {
+                                    if let Some(action) = &mission_state.mission.job.on_success {
+                                        actions.push(action.clone());
+                                    }
+                                }
+                                if mission_state.is_failure() {
+                                    if let Some(action) = &mission_state.mission.job.on_failure {
+                                        actions.push(action.clone());
+                                    }
+                                }
+                                if mission_state.changes_since_last_job_start > 0 && mission_state.auto_refresh.is_enabled() {
+                                    // will be ignored if a on_success or on_failures ends the mission
+                                    // or does a rerun already
+                                    actions.push(Action::ReRun);
                                 }
-                            }
-                            if mission_state.changes_since_last_job_start > 0 && mission_state.auto_refresh.is_enabled() {
-                                // will be ignored if a on_success or on_failures ends the mission
-                                // or does a rerun already
-                                actions.push(Action::ReRun);
                             }
                         }
                         CommandExecInfo::Error(e) => {
@@ -258,34 +379,46 @@ fn run_mission(
                             actions.push(action.clone());
                         }
                     }
-                    #[cfg(windows)]
-                    Event::Mouse(MouseEvent { kind: MouseEventKind::ScrollDown, .. }) => {
-                        let action = keybindings.get(key!(down));
-                        if let Some(action) = action {
-                            actions.push(action.clone());
-                        }
-                    }
-                    #[cfg(windows)]
-                    Event::Mouse(MouseEvent { kind: MouseEventKind::ScrollUp, .. }) => {
-                        let action = keybindings.get(key!(up));
-                        if let Some(action) = action {
-                            actions.push(action.clone());
+                    Event::Mouse(mouse_event) => {
+                        if let Some(action) = mission_state.on_mouse_event(mouse_event) {
+                            actions.push(action);
                         }
                     }
                     _ => {}
                 }
-                if let Some(event_source) = event_source {
-                    event_source.unblock(false);
-                }
+                user_event_received = true;
             }
             recv(action_rx) -> action => {
-                actions.push(action?);
+                let ReceivedAction { action, trigger } = action?;
+                mission_state.app_state.task_trigger = trigger;
+                actions.push(action);
+            }
+            recv(shell_rx) -> outcome => {
+                let outcome: ShellOutcome = outcome?;
+                info!("shell command {:?} done: {:?}", outcome.command, outcome.output);
+                mission_state.messages.push(outcome.message());
+            }
+            recv(query_rx) -> query => {
+                let PendingQuery { query, response_tx } = query?;
+                let _ = response_tx.send(mission_state.answer(query));
+            }
+            recv(dashboard.receiver) -> event => {
+                dashboard.handle_event(event?)?;
             }
         }
         for action in actions.drain(..) {
             let mut rerun = false;
             debug!("requested action: {action:?}");
             match action {
+                Action::ApplyFix => match mission_state.apply_current_item_fix() {
+                    Ok(_) => {
+                        mission_state.messages.push(Message::short("Fix applied"));
+                        rerun = true;
+                    }
+                    Err(e) => {
+                        mission_state.messages.push(Message::short(e.to_string()));
+                    }
+                },
                 Action::Back => {
                     if !mission_state.back() {
                         mission_end = Some(DoAfterMission::NextJob(JobRef::Previous));
@@ -300,17 +433,35 @@ fn run_mission(
                     info!("clearing output");
                     mission_state.clear_output();
                 }
+                Action::CopyItem => {
+                    mission_state.copy_current_item();
+                }
                 Action::CopyUnstyledOutput => {
                     mission_state.copy_unstyled_output();
                 }
+                Action::DismissMatchingItems => {
+                    if mission_state.dismiss_matching_items() > 0 {
+                        mission_state.notify_dismissals();
+                    } else {
+                        mission_state
+                            .messages
+                            .push(Message::short("No item with a location matches the search"));
+                    }
+                }
                 Action::DismissTop => {
-                    mission_state.dismiss_top();
+                    if mission_state.dismiss_top() {
+                        mission_state.notify_dismissals();
+                    }
                 }
                 Action::DismissTopItem => {
-                    mission_state.dismiss_top_item();
+                    if mission_state.dismiss_top_item() {
+                        mission_state.notify_dismissals();
+                    }
                 }
                 Action::DismissTopItemType => {
-                    if !mission_state.dismiss_top_item_type() {
+                    if mission_state.dismiss_top_item_type() {
+                        mission_state.notify_dismissals();
+                    } else {
                         mission_state
                             .messages
                             .push(Message::short("No type found for the top item"));
@@ -318,19 +469,42 @@ fn run_mission(
                 }
                 Action::UndismissAll => {
                     mission_state.undismiss_all();
+                    mission_state.notify_dismissals();
                     rerun = true;
                 }
                 Action::UndismissLocation(location) => {
                     mission_state.remove_dismissal(&Dismissal::Location(location));
+                    mission_state.notify_dismissals();
                     rerun = true;
                 }
                 Action::UndismissDiagType(diag_type) => {
                     mission_state.remove_dismissal(&Dismissal::DiagType(diag_type));
+                    mission_state.notify_dismissals();
+                    rerun = true;
+                }
+                Action::UndismissTitleRegex(regex) => {
+                    mission_state.remove_dismissal(&Dismissal::TitleRegex(regex));
+                    mission_state.notify_dismissals();
+                    rerun = true;
+                }
+                Action::UndismissFileGlob(glob) => {
+                    mission_state.remove_dismissal(&Dismissal::FileGlob(glob));
+                    mission_state.notify_dismissals();
+                    rerun = true;
+                }
+                Action::UndismissCombined(combined) => {
+                    mission_state.remove_dismissal(&Dismissal::Combined(combined));
+                    mission_state.notify_dismissals();
                     rerun = true;
                 }
                 Action::OpenUndismissMenu => {
                     mission_state.open_undismiss_menu();
                 }
+                Action::Explain => {
+                    if let Err(e) = mission_state.explain_top_item() {
+                        mission_state.messages.push(Message::short(e.to_string()));
+                    }
+                }
                 Action::Export(export_name) => {
                     let export_name = export_name.clone();
                     mission_state
@@ -362,6 +536,11 @@ fn run_mission(
                     mission_state.next_match();
                 }
                 Action::NoOp => {}
+                Action::OpenInEditor => {
+                    if let Err(e) = open_in_editor(w, &mission_state, headless) {
+                        mission_state.messages.push(Message::short(e.to_string()));
+                    }
+                }
                 Action::OpenJobsMenu => {
                     mission_state.open_jobs_menu();
                 }
@@ -397,6 +576,17 @@ fn run_mission(
                     mission_end = Some(DoAfterMission::Quit);
                     break;
                 }
+                Action::RecordBaseline => match mission_state.record_baseline() {
+                    Ok(count) => {
+                        mission_state.messages.push(Message::short(format!(
+                            "{count} items recorded in baseline"
+                        )));
+                        rerun = true;
+                    }
+                    Err(e) => {
+                        mission_state.messages.push(Message::short(e.to_string()));
+                    }
+                },
                 Action::ReRun => {
                     rerun = true;
                 }
@@ -419,6 +609,33 @@ fn run_mission(
                 Action::Scroll(scroll_command) => {
                     mission_state.apply_scroll_command(scroll_command);
                 }
+                Action::ToggleSearchCaseInsensitive => {
+                    mission_state.toggle_search_option(SearchState::toggle_case_insensitive);
+                }
+                Action::ToggleSearchFilter => {
+                    mission_state.toggle_search_filter();
+                }
+                Action::ToggleSearchRegex => {
+                    mission_state.toggle_search_option(SearchState::toggle_regex);
+                }
+                Action::ToggleSearchWholeWord => {
+                    mission_state.toggle_search_option(SearchState::toggle_whole_word);
+                }
+                Action::SelectNextItem => {
+                    mission_state.select_item(true);
+                }
+                Action::SelectPreviousItem => {
+                    mission_state.select_item(false);
+                }
+                Action::Shell(template) => {
+                    let command =
+                        fill_shell_placeholders(&template, &mission_state.shell_placeholders());
+                    spawn_shell_command(
+                        command,
+                        mission_state.mission.package_directory.clone(),
+                        shell_tx.clone(),
+                    );
+                }
                 Action::ShowItem(show_item_command) => {
                     mission_state.show_item(show_item_command.item_idx);
                 }
@@ -426,6 +643,9 @@ fn run_mission(
                     mission_state.toggle_backtrace(level);
                     rerun = true;
                 }
+                Action::ToggleNewOnly => {
+                    mission_state.toggle_new_only();
+                }
                 Action::TogglePause => match mission_state.auto_refresh {
                     AutoRefresh::Enabled => {
                         mission_state.auto_refresh = AutoRefresh::Paused;
@@ -458,17 +678,82 @@ fn run_mission(
                 }
             }
             if rerun {
-                task_executor.die();
-                task_executor = mission_state.start_computation(&mut executor)?;
+                if let Some(task_executor) = task_executor.take() {
+                    task_executor.die();
+                }
+                task_executor = Some(mission_state.start_computation(&mut executors)?);
+                mission_state.notify_task_started();
                 break; // drop following actions
             }
         }
+        if mission_end.is_none() {
+            // the trigger is kept only for the first task of the next mission
+            mission_state.app_state.task_trigger = None;
+        }
+        if user_event_received {
+            if let Some(event_source) = event_source {
+                event_source.unblock(false);
+            }
+        }
         if !headless {
+            mission_state.dashboard_badges = dashboard.badges(
+                mission_state.is_computing(),
+                &mission_state.mission.job.skin,
+            );
             mission_state.draw(w)?;
         }
         if let Some(mission_end) = mission_end {
-            task_executor.die();
+            if let Some(task_executor) = task_executor.take() {
+                task_executor.die();
+            }
+            dashboard.unfocus(
+                !mission_state.is_computing()
+                    && !mission_state.is_prerequisite_stage()
+                    && mission_state.changes_since_last_job_start == 0,
+            )?;
+            mission_state.cache_report();
             return Ok(mission_end);
         }
     }
 }
+
+/// Open the location of the current item in the user's editor, suspending
+/// the TUI until the editor exits
+fn open_in_editor(
+    w: &mut W,
+    mission_state: &MissionState,
+    headless: bool,
+) -> Result<()> {
+    let Some(location) = mission_state.current_item_location() else {
+        bail!("No location found for this item");
+    };
+    let template = mission_state.mission.settings.editor.as_deref();
+    let mut command = editor_command(template, &location)?;
+    info!("opening editor: {command:?}");
+    if headless {
+        command.status()?;
+        return Ok(());
+    }
+    let mouse_capture = mission_state.mission.settings.mouse_capture;
+    if mouse_capture {
+        w.queue(DisableMouseCapture)?;
+    }
+    w.queue(LeaveAlternateScreen)?;
+    w.queue(cursor::Show)?;
+    w.flush()?;
+    terminal::disable_raw_mode()?;
+    let status = command.status();
+    terminal::enable_raw_mode()?;
+    w.queue(EnterAlternateScreen)?;
+    w.queue(cursor::Hide)?;
+    if mouse_capture {
+        w.queue(EnableMouseCapture)?;
+    }
+    w.queue(Clear(ClearType::All))?;
+    w.flush()?;
+    let status = status.map_err(|e| anyhow::anyhow!("Failed to launch editor: {e}"))?;
+    if !status.success() {
+        bail!("Editor exited with {status}");
+    }
+    Ok(())
+}
@@ -1,8 +1,18 @@
-use crate::*;
+use {
+    crate::*,
+    rustc_hash::FxHashMap,
+};
 
 #[derive(Default)]
 pub struct AppState {
     pub headless: bool,
     /// Dimissals and filtering state
     pub filter: Filter,
+    /// Clients listening to mission events
+    pub subscribers: Subscribers,
+    /// The last report of every job run during the session
+    pub report_cache: FxHashMap<ConcreteJobRef, Report>,
+    /// The id given by a socket client to the action being executed,
+    /// until the task this action starts, if any, is notified
+    pub task_trigger: Option<String>,
 }
@@ -0,0 +1,362 @@
+use {
+    crate::*,
+    anyhow::Result,
+    std::{
+        mem,
+        thread,
+    },
+    termimad::crossbeam::channel::{
+        Receiver,
+        Sender,
+        unbounded,
+    },
+};
+
+/// Something which happened to one of the jobs of the dashboard
+pub enum DashboardEvent {
+    /// A file watched by the job changed
+    Change { job_idx: usize },
+    /// Information from the task of a job
+    Exec {
+        job_idx: usize,
+        task_id: usize,
+        info: CommandExecInfo,
+    },
+}
+
+/// A job run concurrently with the displayed mission, so that its
+/// state stays visible and its report available without waiting
+struct DashboardJob<'s> {
+    mission: Mission<'s>,
+    executor: MissionExecutor,
+    _watcher: Watcher,
+    task: Option<TaskExecutor>,
+    /// incremented on every task start, so that the lines of killed
+    /// tasks can be recognized and discarded
+    task_id: usize,
+    report_maker: ReportMaker,
+    output: CommandOutput,
+    result: CommandResult,
+    /// whether files changed since the start of the last task
+    dirty: bool,
+    /// whether the job is the one of the mission, which then runs it
+    /// in place of the dashboard
+    focused: bool,
+}
+
+/// The jobs to keep running in the background, whatever the job
+/// the user is looking at
+pub struct Dashboard<'s> {
+    jobs: Vec<DashboardJob<'s>>,
+    tx: Sender<DashboardEvent>,
+    pub receiver: Receiver<DashboardEvent>,
+}
+
+impl<'s> DashboardJob<'s> {
+    fn is_computing(&self) -> bool {
+        self.task.is_some()
+    }
+    fn start(
+        &mut self,
+        job_idx: usize,
+        tx: &Sender<DashboardEvent>,
+    ) -> Result<()> {
+        self.kill();
+        self.task_id += 1;
+        self.dirty = false;
+        self.output = CommandOutput::default();
+        self.report_maker.start(&self.mission);
+        let task = self.executor.start(Task {
+            backtrace: None,
+            grace_period: self.mission.job.grace_period(),
+        })?;
+        let task_id = self.task_id;
+        forward(task.line_receiver.clone(), tx.clone(), move |info| {
+            DashboardEvent::Exec {
+                job_idx,
+                task_id,
+                info,
+            }
+        });
+        self.task = Some(task);
+        Ok(())
+    }
+    fn kill(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.die();
+        }
+    }
+    /// Whether the job should be (re)started by the dashboard
+    fn needs_start(&self) -> bool {
+        !self.focused
+            && !self.is_computing()
+            && (self.dirty || matches!(self.result, CommandResult::None))
+    }
+    fn badges(
+        &self,
+        computing: bool,
+        skin: &BaconSkin,
+    ) -> Vec<TString> {
+        let label = self.mission.concrete_job_ref.badge_label();
+        let report = self.result.report();
+        let (fg, bg) = if computing {
+            (skin.job_label_badge_fg(), skin.job_label_badge_bg())
+        } else if let Some(report) = report {
+            if self.mission.is_success(report) {
+                (skin.test_pass_badge_fg(), skin.test_pass_badge_bg())
+            } else {
+                (skin.errors_badge_fg(), skin.errors_badge_bg())
+            }
+        } else if let CommandResult::Failure(_) = self.result {
+            (skin.errors_badge_fg(), skin.errors_badge_bg())
+        } else {
+            (skin.project_name_badge_fg(), skin.project_name_badge_bg())
+        };
+        let mut badges = vec![TString::badge(&label, fg, bg)];
+        if let Some(report) = report {
+            let stats = &report.stats;
+            if stats.errors > 0 {
+                badges.push(TString::num_badge(
+                    stats.errors,
+                    "error",
+                    skin.errors_badge_fg(),
+                    skin.errors_badge_bg(),
+                ));
+            }
+            if stats.test_fails > 0 {
+                badges.push(TString::num_badge(
+                    stats.test_fails,
+                    "fail",
+                    skin.test_fails_badge_fg(),
+                    skin.test_fails_badge_bg(),
+                ));
+            }
+            if stats.warnings > 0 {
+                badges.push(TString::num_badge(
+                    stats.warnings,
+                    "warning",
+                    skin.warnings_badge_fg(),
+                    skin.warnings_badge_bg(),
+                ));
+            }
+        }
+        badges
+    }
+}
+
+impl<'s> Dashboard<'s> {
+    /// Prepare the jobs of the dashboard (they're started on the
+    /// first call to `focus`)
+    pub fn new(
+        context: &Context,
+        settings: &'s Settings,
+    ) -> Result<Self> {
+        let (tx, receiver) = unbounded();
+        let mut jobs = Vec::new();
+        for (job_idx, concrete_job_ref) in settings.concurrent_jobs.iter().enumerate() {
+            let job = Job::from_concrete_ref(concrete_job_ref, settings)?;
+            let mission = context.mission(concrete_job_ref.clone(), &job, settings)?;
+            let watcher = Watcher::new(&mission.paths_to_watch, mission.ignorer())?;
+            forward(watcher.receiver.clone(), tx.clone(), move |()| {
+                DashboardEvent::Change { job_idx }
+            });
+            jobs.push(DashboardJob {
+                executor: MissionExecutor::new(&mission)?,
+                _watcher: watcher,
+                task: None,
+                task_id: 0,
+                report_maker: ReportMaker::new(&mission),
+                output: CommandOutput::default(),
+                result: CommandResult::None,
+                dirty: false,
+                focused: false,
+                mission,
+            });
+        }
+        Ok(Self { jobs, tx, receiver })
+    }
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+    /// Let the mission run the job with this ref, if it's one of the
+    /// dashboard, and start the other jobs when needed
+    ///
+    /// Return the result of the last run of the focused job when it's
+    /// still up to date, so that the mission doesn't have to run it again.
+    pub fn focus(
+        &mut self,
+        concrete_job_ref: &ConcreteJobRef,
+    ) -> Result<Option<CommandResult>> {
+        let mut up_to_date_result = None;
+        for job in &mut self.jobs {
+            job.focused = job.mission.concrete_job_ref == *concrete_job_ref;
+            if job.focused {
+                if !job.is_computing() && !job.dirty && job.result.report().is_some() {
+                    up_to_date_result = Some(job.result.clone());
+                }
+                job.kill();
+            }
+        }
+        self.start_needed()?;
+        Ok(up_to_date_result)
+    }
+    /// Give back the focused job to the dashboard, telling whether the
+    /// result the mission last gave is still up to date
+    pub fn unfocus(
+        &mut self,
+        up_to_date: bool,
+    ) -> Result<()> {
+        for job in &mut self.jobs {
+            if job.focused {
+                job.focused = false;
+                job.dirty |= !up_to_date;
+            }
+        }
+        self.start_needed()
+    }
+    /// Store the result of the focused job, as computed by the mission
+    pub fn set_focused_result(
+        &mut self,
+        result: &CommandResult,
+    ) {
+        for job in &mut self.jobs {
+            if job.focused {
+                job.result = result.clone();
+                job.dirty = false;
+            }
+        }
+    }
+    fn start_needed(&mut self) -> Result<()> {
+        for (job_idx, job) in self.jobs.iter_mut().enumerate() {
+            if job.needs_start() {
+                job.start(job_idx, &self.tx)?;
+            }
+        }
+        Ok(())
+    }
+    pub fn handle_event(
+        &mut self,
+        event: DashboardEvent,
+    ) -> Result<()> {
+        match event {
+            DashboardEvent::Change { job_idx } => {
+                let job = &mut self.jobs[job_idx];
+                if job.focused {
+                    return Ok(()); // the mission has its own watcher
+                }
+                if job
+                    .task
+                    .as_mut()
+                    .is_some_and(TaskExecutor::is_in_grace_period)
+                {
+                    return Ok(());
+                }
+                job.dirty = true;
+                if job.mission.job.on_change_strategy() == OnChangeStrategy::KillThenRestart {
+                    job.kill();
+                }
+                self.start_needed()?;
+            }
+            DashboardEvent::Exec {
+                job_idx,
+                task_id,
+                info,
+            } => {
+                let job = &mut self.jobs[job_idx];
+                if task_id != job.task_id || job.focused {
+                    return Ok(()); // line of a killed task
+                }
+                match info {
+                    CommandExecInfo::Line(line) => {
+                        job.report_maker.receive_line(line.into(), &mut job.output);
+                    }
+                    CommandExecInfo::End { status } => {
+                        if let Some(ready) = job.report_maker.end_of_output() {
+                            // the analyzer isn't done, the end will come again when it is
+                            let tx = self.tx.clone();
+                            thread::spawn(move || {
+                                let _ = ready.recv();
+                                let _ = tx.send(DashboardEvent::Exec {
+                                    job_idx,
+                                    task_id,
+                                    info: CommandExecInfo::End { status },
+                                });
+                            });
+                            return Ok(());
+                        }
+                        info!(
+                            "dashboard job {} finished with status: {status:?}",
+                            job.mission.concrete_job_ref
+                        );
+                        job.task = None;
+                        let output = mem::take(&mut job.output);
+                        job.result = match job.report_maker.build_report() {
+                            Ok(report) => CommandResult::build(output, status, report)?,
+                            Err(e) => {
+                                warn!(
+                                    "failed to build the report of dashboard job {}: {e:#}",
+                                    job.mission.concrete_job_ref
+                                );
+                                CommandResult::Failure(Failure {
+                                    error_code: -1,
+                                    output,
+                                    suggest_backtrace: false,
+                                })
+                            }
+                        };
+                        self.start_needed()?;
+                    }
+                    CommandExecInfo::Error(e) => {
+                        warn!(
+                            "error in dashboard job {}: {e}",
+                            job.mission.concrete_job_ref
+                        );
+                        job.task = None;
+                        job.result = CommandResult::Failure(Failure {
+                            error_code: -1,
+                            output: mem::take(&mut job.output),
+                            suggest_backtrace: false,
+                        });
+                    }
+                    CommandExecInfo::Interruption => {}
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Build the badges of all jobs, the focused one being computed
+    /// by the mission
+    pub fn badges(
+        &self,
+        focused_computing: bool,
+        skin: &BaconSkin,
+    ) -> Vec<TString> {
+        self.jobs
+            .iter()
+            .flat_map(|job| {
+                let computing = if job.focused {
+                    focused_computing
+                } else {
+                    job.is_computing()
+                };
+                job.badges(computing, skin)
+            })
+            .collect()
+    }
+}
+
+/// Forward the messages of a receiver to the dashboard channel, until
+/// either end is dropped
+fn forward<T: Send + 'static>(
+    receiver: Receiver<T>,
+    tx: Sender<DashboardEvent>,
+    to_event: impl Fn(T) -> DashboardEvent + Send + 'static,
+) {
+    thread::spawn(move || {
+        for message in receiver {
+            if tx.send(to_event(message)).is_err() {
+                break;
+            }
+        }
+    });
+}
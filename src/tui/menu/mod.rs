@@ -22,6 +22,12 @@ pub struct Menu<I> {
     view: MenuView,
 }
 
+impl<I: Md + Clone> Default for Menu<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<I: Md + Clone> Menu<I> {
     pub fn new() -> Self {
         Self {
@@ -1,18 +1,35 @@
 use {
     crate::*,
-    anyhow::Result,
-    crokey::KeyCombination,
+    anyhow::{
+        Result,
+        bail,
+    },
+    crokey::{
+        KeyCombination,
+        key,
+    },
+    rustc_hash::FxHashSet,
     std::{
+        borrow::Cow,
         io::Write,
         process::ExitStatus,
-        time::Instant,
+        time::{
+            Duration,
+            Instant,
+        },
     },
     termimad::{
         Area,
         CompoundStyle,
         MadSkin,
+        crossbeam::channel::Receiver,
         crossterm::{
             cursor,
+            event::{
+                MouseButton,
+                MouseEvent,
+                MouseEventKind,
+            },
             execute,
             style::{
                 Attribute,
@@ -24,14 +41,26 @@ use {
             Composite,
         },
     },
+    unicode_width::UnicodeWidthStr,
 };
 
+/// Max delay between the two clicks of a double click
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// Factor applied to the lines scrolled by the down and up keys
+/// to get the ones scrolled by a mouse wheel notch
+const WHEEL_SCROLL_LINES: i32 = 3;
+
 /// State of the TUI application for the duration of a mission
 pub struct MissionState<'a, 'm> {
     /// the longer-living application state
     pub app_state: &'a mut AppState,
     /// the mission to run, with settings
     pub mission: Mission<'m>,
+    /// index of the running (or last run) stage, either one of the
+    /// prerequisites of the mission or, after them, the mission's job
+    stage: usize,
+    /// the report maker of the current stage
     report_maker: ReportMaker,
     /// the lines of a computation in progress
     output: Option<CommandOutput>,
@@ -39,6 +68,19 @@ pub struct MissionState<'a, 'm> {
     wrapped_output: Option<WrappedCommandOutput>,
     /// result of a command, hopefully a report
     pub cmd_result: CommandResult,
+    /// whether the report comes from a previous run of the job, and is
+    /// displayed only until the running one ends
+    stale: bool,
+    /// the fingerprints of the items of the last report of the job
+    previous_fingerprints: Option<Vec<ItemFingerprint>>,
+    /// the difference between the report and the previous one
+    run_diff: Option<RunDiff>,
+    /// whether only the items which weren't in the previous run are shown
+    new_only: bool,
+    /// whether only the items matching the search are shown
+    search_filter: bool,
+    /// the items matching the search, when filtering on it
+    matching_items: Option<FxHashSet<usize>>,
     /// a report wrapped for the size of the console
     wrapped_report: Option<WrappedReport>,
     /// screen width
@@ -69,6 +111,8 @@ pub struct MissionState<'a, 'm> {
     help_line: Option<HelpLine>,
     /// the help page displayed over the rest, if any
     help_page: Option<HelpPage>,
+    /// the explanation of an error code, displayed over the rest, if any
+    explain_page: Option<ExplainPage>,
     /// display the raw output instead of the report
     raw_output: bool,
     /// whether auto-refresh is enabled
@@ -86,6 +130,17 @@ pub struct MissionState<'a, 'm> {
     /// the prefered scroll anchor, which we try to stick to when the user didn't volontarily
     /// scroll to another item
     pub scroll_anchor: ScrollAnchor,
+    /// the badges of the concurrent jobs, drawn below the job's ones
+    pub dashboard_badges: Vec<TString>,
+    /// the row of the first line of content, when content was drawn
+    content_top: Option<u16>,
+    /// the row and width of the job badges, as drawn
+    job_badges_zone: Option<(u16, u16)>,
+    /// when the last left click occurred, to detect double clicks
+    last_click: Option<Instant>,
+    /// the item selected with the item cursor, on which item actions
+    /// apply instead of the top item
+    selected_item_idx: Option<usize>,
 }
 
 impl<'a, 'm> MissionState<'a, 'm> {
@@ -93,7 +148,7 @@ impl<'a, 'm> MissionState<'a, 'm> {
         app_state: &'a mut AppState,
         mission: Mission<'m>,
     ) -> Result<Self> {
-        let report_maker = ReportMaker::new(&mission);
+        let report_maker = ReportMaker::new(mission.prerequisites.first().unwrap_or(&mission));
         let mut status_skin = MadSkin::default();
         let skin = mission.job.skin;
         status_skin
@@ -116,10 +171,17 @@ impl<'a, 'm> MissionState<'a, 'm> {
         let show_changes_count = mission.job.show_changes_count();
         let scroll_anchor = mission.job.scroll_anchor();
         Ok(Self {
+            stage: 0,
             report_maker,
             output: None,
             wrapped_output: None,
             cmd_result: CommandResult::None,
+            stale: false,
+            previous_fingerprints: None,
+            run_diff: None,
+            new_only: false,
+            search_filter: false,
+            matching_items: None,
             wrapped_report: None,
             width,
             height,
@@ -135,6 +197,7 @@ impl<'a, 'm> MissionState<'a, 'm> {
             top_item_idx: 0,
             help_line,
             help_page: None,
+            explain_page: None,
             raw_output: false,
             auto_refresh: AutoRefresh::Enabled,
             changes_since_last_job_start: 0,
@@ -144,6 +207,11 @@ impl<'a, 'm> MissionState<'a, 'm> {
             app_state,
             mission,
             scroll_anchor,
+            dashboard_badges: Vec::new(),
+            content_top: None,
+            job_badges_zone: None,
+            last_click: None,
+            selected_item_idx: None,
         })
     }
     pub fn open_jobs_menu(&mut self) {
@@ -212,6 +280,88 @@ impl<'a, 'm> MissionState<'a, 'm> {
             .nth(self.scroll)
             .map(|line| line.item_idx)
     }
+    /// Return the selected item if it's displayed, the top item otherwise
+    pub fn current_item_idx(&self) -> Option<usize> {
+        if let Some(selected) = self.displayed_selected_item_idx() {
+            return Some(selected);
+        }
+        self.top_item_idx()
+    }
+    /// Return the selected item, unless it's not among the displayed lines
+    /// (eg it was dismissed)
+    fn displayed_selected_item_idx(&self) -> Option<usize> {
+        let selected = self.selected_item_idx?;
+        self.report_to_draw()?;
+        self.lines_to_draw()
+            .any(|line| line.item_idx == selected)
+            .then_some(selected)
+    }
+    /// Move the item cursor to the next item, or to the previous one
+    /// when `forward` is false, selecting the top item if there was no
+    /// selection
+    pub fn select_item(
+        &mut self,
+        forward: bool,
+    ) {
+        if self.report_to_draw().is_none() {
+            return;
+        }
+        let mut items: Vec<usize> = self.lines_to_draw().map(|line| line.item_idx).collect();
+        items.dedup();
+        let new_selection = match self.displayed_selected_item_idx() {
+            Some(selected) => {
+                let Some(pos) = items.iter().position(|&item_idx| item_idx == selected) else {
+                    return;
+                };
+                let pos = if forward {
+                    (pos + 1).min(items.len() - 1)
+                } else {
+                    pos.saturating_sub(1)
+                };
+                items[pos]
+            }
+            None => {
+                let Some(top_item_idx) = self.top_item_idx() else {
+                    return;
+                };
+                top_item_idx
+            }
+        };
+        self.selected_item_idx = Some(new_selection);
+        self.make_item_visible(new_selection);
+    }
+    /// Scroll, if needed, so that the lines of an item are visible,
+    /// or at least its first lines when it's taller than the page
+    fn make_item_visible(
+        &mut self,
+        item_idx: usize,
+    ) {
+        let mut item_lines = self
+            .lines_to_draw()
+            .enumerate()
+            .filter(|(_, line)| line.item_idx == item_idx)
+            .map(|(line_idx, _)| line_idx);
+        let Some(first) = item_lines.next() else {
+            return;
+        };
+        let last = item_lines.last().unwrap_or(first);
+        let page_height = self.page_height();
+        if first < self.scroll {
+            self.scroll = first;
+        } else if last >= self.scroll + page_height {
+            self.scroll = first.min(last + 1 - page_height);
+        }
+        self.fix_scroll();
+        self.scrolled_to_top_item_idx = self.top_item_idx();
+    }
+    /// Return the absolute location of the current item, if any
+    pub fn current_item_location(&self) -> Option<Location> {
+        let report = self.cmd_result.report()?;
+        let location = report.item_location(self.current_item_idx()?)?;
+        let mut location: Location = location.parse().ok()?;
+        location.path = self.mission.make_absolute(location.path);
+        Some(location)
+    }
     pub fn focus_search(&mut self) {
         self.search.focus_with_mode(SearchMode::Pattern);
         self.show_selected_found();
@@ -220,9 +370,50 @@ impl<'a, 'm> MissionState<'a, 'm> {
         self.search.focus_with_mode(SearchMode::ItemIdx);
         self.show_selected_found();
     }
+    /// Apply the machine applicable suggestions of the current item to the
+    /// source files, returning the number of modified files
+    pub fn apply_current_item_fix(&mut self) -> Result<usize> {
+        if self.stale || self.is_computing() || self.changes_since_last_job_start > 0 {
+            bail!("The report isn't up to date, wait for the end of the job");
+        }
+        let Some(item_idx) = self.current_item_idx() else {
+            bail!("No item to fix");
+        };
+        let Some(report) = self.cmd_result.report_mut() else {
+            bail!("No report");
+        };
+        let Some(diagnostic) = report.item_diagnostics.get(&item_idx) else {
+            bail!("No structured diagnostic for this item (is the analyzer cargo_json?)");
+        };
+        let replacements = diagnostic.fix_replacements(&self.mission);
+        if replacements.is_empty() {
+            bail!("No machine applicable suggestion for this item");
+        }
+        let modified_files = apply_replacements(replacements)?;
+        // the offsets of the other suggestions aren't valid anymore
+        report.item_diagnostics.clear();
+        Ok(modified_files)
+    }
+    /// Write all the items of the report in the baseline file of the job,
+    /// returning their number
+    pub fn record_baseline(&self) -> Result<usize> {
+        let Some(path) = self.mission.baseline_path() else {
+            bail!("No baseline file defined for this job");
+        };
+        let Some(report) = self.cmd_result.report() else {
+            bail!("No report to record");
+        };
+        if self.stale || self.is_prerequisite_stage() {
+            bail!("The report isn't the one of the current job run");
+        }
+        let baseline = Baseline::of_report(report);
+        baseline.write(&path)?;
+        info!("baseline recorded in {}", path.display());
+        Ok(baseline.fingerprints.len())
+    }
     pub fn dismiss_top_item(&mut self) -> bool {
         if let Some(report) = self.cmd_result.report() {
-            if let Some(item_idx) = self.top_item_idx() {
+            if let Some(item_idx) = self.current_item_idx() {
                 if let Some(location) = report.item_location(item_idx) {
                     let location = location.to_string();
                     self.app_state.filter.add(Dismissal::Location(location));
@@ -235,7 +426,7 @@ impl<'a, 'm> MissionState<'a, 'm> {
     }
     pub fn dismiss_top_item_type(&mut self) -> bool {
         if let Some(report) = self.cmd_result.report() {
-            if let Some(item_idx) = self.top_item_idx() {
+            if let Some(item_idx) = self.current_item_idx() {
                 if let Some(diag_type) = report.item_diag_type(item_idx) {
                     let diag_type = diag_type.to_string();
                     self.app_state.filter.add(Dismissal::DiagType(diag_type));
@@ -251,10 +442,34 @@ impl<'a, 'm> MissionState<'a, 'm> {
     pub fn dismiss_top(&mut self) -> bool {
         self.dismiss_top_item_type() || self.dismiss_top_item()
     }
+    /// Dismiss the locations of all the items matching the search, when
+    /// filtering on it, and return the number of dismissed items
+    pub fn dismiss_matching_items(&mut self) -> usize {
+        self.update_search();
+        let (Some(report), Some(matching_items)) = (self.cmd_result.report(), &self.matching_items)
+        else {
+            return 0;
+        };
+        let mut item_idxs: Vec<usize> = matching_items.iter().copied().collect();
+        item_idxs.sort_unstable();
+        let locations: Vec<String> = item_idxs
+            .into_iter()
+            .filter_map(|item_idx| report.item_location(item_idx))
+            .map(|location| location.to_string())
+            .collect();
+        let count = locations.len();
+        for location in locations {
+            self.app_state.filter.add(Dismissal::Location(location));
+        }
+        if count > 0 {
+            self.apply_filter();
+        }
+        count
+    }
     pub fn undismiss_all(&mut self) {
         if let Some(report) = self.cmd_result.report_mut() {
             self.app_state.filter.restore_dismissed_lines(report);
-            self.app_state.filter = Default::default();
+            self.app_state.filter.clear();
             self.apply_filter();
         }
     }
@@ -291,36 +506,77 @@ impl<'a, 'm> MissionState<'a, 'm> {
             self.search.unfocus_and_clear();
         } else if self.help_page.is_some() {
             self.help_page = None;
+        } else if self.explain_page.is_some() {
+            self.explain_page = None;
+        } else if self.search_filter {
+            self.toggle_search_filter();
         } else if self.search.input_has_content() {
             self.search.clear();
+        } else if self.selected_item_idx.is_some() {
+            self.selected_item_idx = None;
         } else {
             return false;
         }
         true
     }
     pub fn copy_unstyled_output(&mut self) {
+        let mut content = String::new();
+        for line in self.lines_to_draw() {
+            content.push_str(&line.content.to_raw());
+            content.push('\n');
+        }
+        self.copy_to_clipboard(content, "Output copied to clipboard");
+    }
+    /// Copy the unstyled lines of the current item to the clipboard
+    pub fn copy_current_item(&mut self) {
+        let content = self.current_item_idx().and_then(|item_idx| {
+            let report = self.cmd_result.report()?;
+            let mut content = String::new();
+            for line in report.lines.iter().filter(|line| line.item_idx == item_idx) {
+                content.push_str(&line.content.to_raw());
+                content.push('\n');
+            }
+            Some(content)
+        });
+        match content {
+            Some(content) => self.copy_to_clipboard(content, "Item copied to clipboard"),
+            None => self.messages.push(Message::short("No item to copy")),
+        }
+    }
+    fn copy_to_clipboard(
+        &mut self,
+        content: String,
+        done: &'static str,
+    ) {
         let message = {
             #[cfg(feature = "clipboard")]
             match arboard::Clipboard::new() {
                 Ok(mut clipboard) => {
-                    let mut content = String::new();
-                    for line in self.lines_to_draw() {
-                        content.push_str(&line.content.to_raw());
-                        content.push('\n');
-                    }
                     let _ = clipboard.set_text(content);
-                    "Output copied to clipboard"
+                    done
                 }
                 Err(e) => {
-                    error!("Failed to copy output to clipboard: {}", e);
+                    error!("Failed to copy to clipboard: {}", e);
                     "Clipboard error - nothing copied"
                 }
             }
             #[cfg(not(feature = "clipboard"))]
-            "clipboard feature not enabled : nothing copied"
+            {
+                let _ = (content, done);
+                "clipboard feature not enabled : nothing copied"
+            }
         };
         self.messages.push(Message::short(message));
     }
+    /// Change how the search pattern is matched, then search again
+    pub fn toggle_search_option(
+        &mut self,
+        toggle: fn(&mut SearchState),
+    ) {
+        toggle(&mut self.search);
+        self.update_search();
+        self.show_selected_found();
+    }
     pub fn next_match(&mut self) {
         self.search.next_match();
         self.show_selected_found();
@@ -370,16 +626,99 @@ impl<'a, 'm> MissionState<'a, 'm> {
         }
         None
     }
+    /// handle a mouse event, return the action to run, if any
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+    ) -> Option<Action> {
+        let is_click = mouse_event.kind == MouseEventKind::Down(MouseButton::Left);
+        let double_click = is_click
+            && self
+                .last_click
+                .is_some_and(|t| t.elapsed() < DOUBLE_CLICK_DELAY);
+        if is_click {
+            self.last_click = Some(Instant::now());
+        }
+        if let Dialog::Menu(menu) = &mut self.dialog {
+            let action = menu.state.on_mouse_event(mouse_event, double_click);
+            if action.is_some() {
+                self.close_menu();
+            }
+            return action.or(Some(Action::NoOp));
+        }
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.wheel_action(key!(down)),
+            MouseEventKind::ScrollUp => self.wheel_action(key!(up)),
+            _ if is_click => self.on_click(mouse_event.column, mouse_event.row),
+            _ => None,
+        }
+    }
+    /// The action of a wheel move, which is the one bound to the down
+    /// or up key, with line scrolls multiplied by `WHEEL_SCROLL_LINES`
+    fn wheel_action(
+        &self,
+        key: KeyCombination,
+    ) -> Option<Action> {
+        match self.mission.settings.keybindings.get(key)? {
+            Action::Scroll(ScrollCommand::Lines(n)) => {
+                Some(Action::Scroll(ScrollCommand::Lines(n * WHEEL_SCROLL_LINES)))
+            }
+            action => Some(action.clone()),
+        }
+    }
+    fn on_click(
+        &mut self,
+        x: u16,
+        y: u16,
+    ) -> Option<Action> {
+        if let Some((badges_y, badges_width)) = self.job_badges_zone {
+            if y == badges_y && x < badges_width {
+                return Some(Action::OpenJobsMenu);
+            }
+        }
+        let content_top = self.content_top?;
+        if y < content_top {
+            return None;
+        }
+        let line_idx = self.scroll + usize::from(y - content_top);
+        if line_idx >= self.scroll + self.page_height() {
+            return None;
+        }
+        self.report_to_draw()?;
+        let line = self.lines_to_draw().nth(line_idx)?;
+        let item_idx = line.item_idx;
+        let is_location = line.location().is_some();
+        self.selected_item_idx = Some(item_idx);
+        if !is_location {
+            return Some(Action::NoOp);
+        }
+        info!("click on the location of item {item_idx}");
+        Some(self.mission.settings.on_location_click.clone())
+    }
     pub fn update_search(&mut self) {
         if self.search.is_up_to_date() {
             return;
         }
-        let founds = if self.search.input_has_content() {
-            let search = self.search.search();
-            let lines = self.lines_to_draw();
-            search.search_lines(lines, 0)
-        } else {
-            Vec::new()
+        let search = self
+            .search
+            .input_has_content()
+            .then(|| self.search.search())
+            .flatten();
+        self.matching_items = None;
+        if self.search_filter && self.report_to_draw().is_some() {
+            if let Some(search) = &search {
+                let lines: Vec<&Line> = self.lines_to_draw().collect();
+                let matching_items = search
+                    .search_lines(lines.iter().copied(), 0)
+                    .iter()
+                    .map(|found| lines[found.line_idx].item_idx)
+                    .collect();
+                self.matching_items = Some(matching_items);
+            }
+        }
+        let founds = match search {
+            Some(search) => search.search_lines(self.lines_to_draw(), 0),
+            None => Vec::new(),
         };
         self.search.set_founds(founds);
     }
@@ -395,7 +734,9 @@ impl<'a, 'm> MissionState<'a, 'm> {
         // account as we're only adding lines in the raw output where there's
         // no filtering
         let lines = self.lines_to_draw_unfiltered();
-        let search = self.search.search();
+        let Some(search) = self.search.search() else {
+            return;
+        };
         if line_count_before >= lines.len() {
             warn!("inconsistent line_count_before");
             return;
@@ -475,16 +816,59 @@ impl<'a, 'm> MissionState<'a, 'm> {
         }
         self.search.touch();
     }
+    /// Tell the analyzer the output of the task ended, and get a receiver
+    /// signaling when the task can be finished without blocking, if it
+    /// can't already
+    pub fn end_of_output(&mut self) -> Option<Receiver<()>> {
+        self.report_maker.end_of_output()
+    }
     pub fn finish_task(
         &mut self,
         exit_status: ExitStatus,
     ) -> Result<()> {
         let output = self.take_output().unwrap_or_default();
-        let result = self.report_maker.build_result(output, exit_status)?;
+        let result = match self.report_maker.build_report() {
+            Ok(report) => CommandResult::build(output, exit_status, report)?,
+            Err(e) => {
+                warn!("failed to build the report: {e:#}");
+                self.messages.push(Message::short(format!("{e:#}")));
+                CommandResult::Failure(Failure {
+                    error_code: -1,
+                    output,
+                    suggest_backtrace: false,
+                })
+            }
+        };
+        self.stale = false;
         self.set_result(result);
         Ok(())
     }
-    fn set_result(
+    /// Display the report the job produced the last time it was run in
+    /// this session, if any, marked as stale
+    pub fn show_cached_report(&mut self) {
+        let cached = self
+            .app_state
+            .report_cache
+            .get(&self.mission.concrete_job_ref)
+            .cloned();
+        if let Some(report) = cached {
+            debug!("showing cached report");
+            self.set_result(CommandResult::Report(report));
+            self.stale = true;
+        }
+    }
+    /// Keep the report of the job, for when the user comes back to it
+    pub fn cache_report(&mut self) {
+        if self.is_prerequisite_stage() {
+            return; // the report may be the one of a prerequisite
+        }
+        if let Some(report) = self.cmd_result.report() {
+            self.app_state
+                .report_cache
+                .insert(self.mission.concrete_job_ref.clone(), report.clone());
+        }
+    }
+    pub fn set_result(
         &mut self,
         mut cmd_result: CommandResult,
     ) {
@@ -518,12 +902,36 @@ impl<'a, 'm> MissionState<'a, 'm> {
         // we keep the scroll when the number of lines didn't change
         let fix_scroll = self.cmd_result.lines_len() != cmd_result.lines_len();
 
+        // the selection follows its item, found by fingerprint
+        let selected_fingerprint = self.selected_item_idx.and_then(|selected| {
+            self.cmd_result.report().and_then(|report| {
+                report
+                    .item_fingerprints()
+                    .into_iter()
+                    .find(|(item_idx, _)| *item_idx == selected)
+            })
+        });
+        self.selected_item_idx = selected_fingerprint.and_then(|(_, fingerprint)| {
+            cmd_result.report().and_then(|report| {
+                report
+                    .item_fingerprints()
+                    .into_iter()
+                    .find(|(_, f)| *f == fingerprint)
+                    .map(|(item_idx, _)| item_idx)
+            })
+        });
+
         self.wrapped_report = None;
         self.wrapped_output = None;
         self.cmd_result = cmd_result;
 
         // we apply the filter
         self.apply_filter();
+        self.update_run_diff();
+        if self.search_filter {
+            // the matching items must be known before scrolling
+            self.update_search();
+        }
 
         self.computing = false;
         self.raw_output = false;
@@ -542,6 +950,72 @@ impl<'a, 'm> MissionState<'a, 'm> {
         // we do all exports which are set to auto
         self.mission.settings.exports.do_auto_exports(self);
     }
+    /// Compare the items of the report with the ones of the previous run
+    fn update_run_diff(&mut self) {
+        let report = self
+            .cmd_result
+            .report()
+            .filter(|_| !self.is_prerequisite_stage());
+        let Some(report) = report else {
+            self.run_diff = None;
+            return;
+        };
+        self.run_diff = self
+            .previous_fingerprints
+            .as_ref()
+            .map(|previous| RunDiff::new(previous, report));
+        self.previous_fingerprints = Some(
+            report
+                .item_fingerprints()
+                .into_iter()
+                .map(|(_, fingerprint)| fingerprint)
+                .collect(),
+        );
+    }
+    pub fn run_diff(&self) -> Option<&RunDiff> {
+        self.run_diff.as_ref()
+    }
+    pub fn is_new_only(&self) -> bool {
+        self.new_only
+    }
+    pub fn toggle_new_only(&mut self) {
+        let visible_state = self.visible_scroll_state();
+        self.new_only ^= true;
+        self.search.touch();
+        self.update_search();
+        self.restore_visible_scroll_state(visible_state);
+        self.show_selected_found();
+    }
+    pub fn is_search_filter(&self) -> bool {
+        self.search_filter
+    }
+    /// Toggle the display of only the items matching the search, focusing
+    /// the search input if there's no search yet
+    pub fn toggle_search_filter(&mut self) {
+        let visible_state = self.visible_scroll_state();
+        self.search_filter ^= true;
+        self.search.touch();
+        self.update_search();
+        self.restore_visible_scroll_state(visible_state);
+        self.show_selected_found();
+        if self.search_filter && !self.search.input_has_content() {
+            self.focus_search();
+        }
+    }
+    /// Return the report for an export requested by the user: the whole
+    /// report, or only the items matching the search when filtering on it
+    pub fn report_to_export(&self) -> Option<Cow<'_, Report>> {
+        let report = self.cmd_result.report()?;
+        let Some(matching_items) = &self.matching_items else {
+            return Some(Cow::Borrowed(report));
+        };
+        let mut report = report.clone();
+        report
+            .lines
+            .retain(|line| matching_items.contains(&line.item_idx));
+        report.lines_changed();
+        Some(Cow::Owned(report))
+    }
     pub fn is_computing(&self) -> bool {
         self.computing
     }
@@ -549,27 +1023,76 @@ impl<'a, 'm> MissionState<'a, 'm> {
         debug!("state.clear");
         self.take_output();
         self.cmd_result = CommandResult::None;
+        self.stale = false;
         self.search.touch();
     }
-    /// Start a new task on the current mission
+    /// Start a new task on the current mission, from its first stage
+    ///
+    /// The executors are the ones of the stages, in order.
     pub fn start_computation(
         &mut self,
-        executor: &mut MissionExecutor,
+        executors: &mut [MissionExecutor],
     ) -> Result<TaskExecutor> {
         debug!("state.start_computation");
         self.computation_starts();
-        executor.start(self.new_task())
+        self.start_task(executors)
+    }
+    /// Start the command of the current stage
+    pub fn start_task(
+        &self,
+        executors: &mut [MissionExecutor],
+    ) -> Result<TaskExecutor> {
+        executors[self.stage].start(self.new_task())
+    }
+    /// If the task of a prerequisite just succeeded, start the
+    /// next stage and return its task executor
+    pub fn start_next_stage(
+        &mut self,
+        executors: &mut [MissionExecutor],
+    ) -> Result<Option<TaskExecutor>> {
+        if self.stage >= self.mission.prerequisites.len() || !self.is_success() {
+            return Ok(None);
+        }
+        info!(
+            "prerequisite {} passed",
+            self.stage_mission().concrete_job_ref
+        );
+        self.set_stage(self.stage + 1);
+        self.stage_starts();
+        self.start_task(executors).map(Some)
+    }
+    /// The mission of the current stage, either a prerequisite
+    /// or the mission itself
+    pub fn stage_mission(&self) -> &Mission<'m> {
+        self.mission.stage(self.stage)
+    }
+    fn set_stage(
+        &mut self,
+        stage: usize,
+    ) {
+        if stage != self.stage {
+            self.stage = stage;
+            self.report_maker = ReportMaker::new(self.stage_mission());
+        }
     }
     /// Called when a task has started
     pub fn computation_starts(&mut self) {
-        if !self.mission.job.background() {
+        self.set_stage(0);
+        self.changes_since_last_job_start = 0;
+        self.stage_starts();
+    }
+    fn stage_starts(&mut self) {
+        if !self.stage_mission().job.background() {
             self.clear();
         }
-        self.report_maker.start(&self.mission);
+        self.report_maker.start(self.mission.stage(self.stage));
         self.computing = true;
-        self.changes_since_last_job_start = 0;
         self.search.touch();
     }
+    /// Whether the current stage is a prerequisite of the mission's job
+    pub fn is_prerequisite_stage(&self) -> bool {
+        self.stage < self.mission.prerequisites.len()
+    }
     pub fn computation_stops(&mut self) {
         self.computing = false;
     }
@@ -649,22 +1172,44 @@ impl<'a, 'm> MissionState<'a, 'm> {
     /// close the help and return true if it was open,
     /// return false otherwise
     pub fn close_help(&mut self) -> bool {
-        if self.help_page.is_some() {
+        if self.help_page.is_some() || self.explain_page.is_some() {
             self.help_page = None;
+            self.explain_page = None;
             true
         } else {
             false
         }
     }
+    /// whether a help page (the general help or an explanation) is displayed
     pub fn is_help(&self) -> bool {
-        self.help_page.is_some()
+        self.help_page.is_some() || self.explain_page.is_some()
     }
     pub fn toggle_help(&mut self) {
+        self.explain_page = None;
         self.help_page = match self.help_page {
             Some(_) => None,
             None => Some(HelpPage::new(self.mission.settings)),
         };
     }
+    /// Open a page explaining the error code or lint of the current item
+    pub fn explain_top_item(&mut self) -> Result<()> {
+        let code = self
+            .cmd_result
+            .report()
+            .zip(self.current_item_idx())
+            .and_then(|(report, item_idx)| report.item_code(item_idx));
+        let Some(code) = code else {
+            bail!("No error code or lint found for this item");
+        };
+        let page = ExplainPage::new(
+            &code,
+            &self.mission.package_directory,
+            self.mission.settings,
+        )?;
+        self.help_page = None;
+        self.explain_page = Some(page);
+        Ok(())
+    }
     pub fn toggle_summary_mode(&mut self) {
         let visible_state = self.visible_scroll_state();
         self.summary ^= true;
@@ -699,8 +1244,13 @@ impl<'a, 'm> MissionState<'a, 'm> {
         let lines = self.lines_to_draw();
         lines.count()
     }
+    /// height of the dashboard line: 1 when there are concurrent jobs, else 0
+    fn dashboard_height(&self) -> u16 {
+        u16::from(!self.dashboard_badges.is_empty())
+    }
     fn page_height(&self) -> usize {
-        self.height.max(3) as usize - 3
+        let top_height = 3 + self.dashboard_height();
+        (self.height.max(top_height) - top_height) as usize
     }
     pub fn resize(
         &mut self,
@@ -742,6 +1292,8 @@ impl<'a, 'm> MissionState<'a, 'm> {
     ) {
         if let Some(help_page) = self.help_page.as_mut() {
             help_page.apply_scroll_command(cmd);
+        } else if let Some(explain_page) = self.explain_page.as_mut() {
+            explain_page.apply_scroll_command(cmd);
         } else {
             self.scroll = cmd.apply(self.scroll, self.content_height(), self.page_height());
             self.scrolled_to_top_item_idx = self.top_item_idx();
@@ -757,8 +1309,9 @@ impl<'a, 'm> MissionState<'a, 'm> {
         // Search input, never wider than the terminal
         if self.search.must_be_drawn() {
             let search_width = (self.width / 4).clamp(9, 27).min(self.width);
-            if search_width > 1 {
-                // draw_prefixed_input requires a width > 1
+            if search_width > 4 {
+                // draw_prefixed_input requires a width > its prefix, which
+                // is at most 4 wide
                 let skin = self.mission.job.skin;
                 let csi = format!("\u{1b}[1m\u{1b}[38;5;{}m", skin.search_input_prefix_fg());
                 self.search
@@ -794,12 +1347,28 @@ impl<'a, 'm> MissionState<'a, 'm> {
             skin.project_name_badge_fg(),
             skin.project_name_badge_bg(),
         ));
+        // one badge per prerequisite, telling whether it passed, failed,
+        // is running, or is still to be run
+        for (i, prerequisite) in self.mission.prerequisites.iter().enumerate() {
+            let label = prerequisite.concrete_job_ref.badge_label();
+            let (fg, bg) = if i < self.stage {
+                (skin.test_pass_badge_fg(), skin.test_pass_badge_bg())
+            } else if i > self.stage {
+                (skin.project_name_badge_fg(), skin.project_name_badge_bg())
+            } else if self.computing {
+                (skin.job_label_badge_fg(), skin.job_label_badge_bg())
+            } else {
+                (skin.errors_badge_fg(), skin.errors_badge_bg())
+            };
+            badges.push(TString::badge(&label, fg, bg));
+        }
         let job_label = self.mission.concrete_job_ref.badge_label();
-        badges.push(TString::badge(
-            &job_label,
-            skin.job_label_badge_fg(),
-            skin.job_label_badge_bg(),
-        ));
+        let (fg, bg) = if self.is_prerequisite_stage() {
+            (skin.project_name_badge_fg(), skin.project_name_badge_bg())
+        } else {
+            (skin.job_label_badge_fg(), skin.job_label_badge_bg())
+        };
+        badges.push(TString::badge(&job_label, fg, bg));
         if let CommandResult::Report(report) = &self.cmd_result {
             let stats = &report.stats;
             if stats.errors > 0 {
@@ -860,9 +1429,16 @@ impl<'a, 'm> MissionState<'a, 'm> {
         let skin = self.mission.job.skin;
         goto_line(w, y)?;
         let mut t_line = TLine::default();
-        for badge in self.job_badges() {
+        let mut job_badges_width = 0;
+        // the badges of the project and jobs come first, then the stats
+        let job_badges_count = self.mission.prerequisites.len() + 2;
+        for (i, badge) in self.job_badges().into_iter().enumerate() {
+            if i < job_badges_count {
+                job_badges_width += badge.raw.width() + 1;
+            }
             t_line.add_badge(badge);
         }
+        self.job_badges_zone = Some((y, u16::try_from(job_badges_width).unwrap_or(u16::MAX)));
         let dismissed = self.report_to_draw().map_or(0, |r| r.dismissed_items);
         if dismissed > 0 {
             t_line.add_badge(TString::num_badge(
@@ -872,6 +1448,23 @@ impl<'a, 'm> MissionState<'a, 'm> {
                 skin.dismissed_badge_bg(),
             ));
         }
+        // baselined items are told even when the job passes
+        let baselined = self.cmd_result.report().map_or(0, |r| r.baselined.len());
+        if baselined > 0 {
+            t_line.add_badge(TString::num_badge(
+                baselined,
+                "baselined item",
+                skin.dismissed_badge_fg(),
+                skin.dismissed_badge_bg(),
+            ));
+        }
+        if self.stale {
+            t_line.add_badge(TString::badge(
+                "stale",
+                skin.stale_badge_fg(),
+                skin.stale_badge_bg(),
+            ));
+        }
         if self.show_changes_count {
             t_line.add_badge(TString::num_badge(
                 self.changes_since_last_job_start,
@@ -880,6 +1473,14 @@ impl<'a, 'm> MissionState<'a, 'm> {
                 skin.change_badge_bg(),
             ));
         }
+        if let Some(matching_items) = &self.matching_items {
+            t_line.add_badge(TString::num_badge(
+                matching_items.len(),
+                "matching item",
+                skin.search_filter_badge_fg(),
+                skin.search_filter_badge_bg(),
+            ));
+        }
         if self.search.input_has_content() {
             // bold, colored foreground
             let csi_found = format!("\u{1b}[1m\u{1b}[38;5;{}m", skin.found_fg());
@@ -890,6 +1491,21 @@ impl<'a, 'm> MissionState<'a, 'm> {
         clear_line(w)?;
         Ok(cols)
     }
+    /// draw the line of badges of the concurrent jobs
+    pub fn draw_dashboard(
+        &mut self,
+        w: &mut W,
+        y: u16,
+    ) -> Result<()> {
+        goto_line(w, y)?;
+        let mut t_line = TLine::default();
+        for badge in &self.dashboard_badges {
+            t_line.add_badge(badge.clone());
+        }
+        t_line.draw_in(w, self.width as usize)?;
+        clear_line(w)?;
+        Ok(())
+    }
     /// draw "computing...", the error code if any, or a blank line
     pub fn draw_computing(
         &mut self,
@@ -942,13 +1558,13 @@ impl<'a, 'm> MissionState<'a, 'm> {
     }
     pub fn is_success(&self) -> bool {
         match &self.cmd_result {
-            CommandResult::Report(report) => self.mission.is_success(report),
+            CommandResult::Report(report) => self.stage_mission().is_success(report),
             _ => false,
         }
     }
     pub fn is_failure(&self) -> bool {
         match &self.cmd_result {
-            CommandResult::Report(report) => !self.mission.is_success(report),
+            CommandResult::Report(report) => !self.stage_mission().is_success(report),
             CommandResult::Failure(_) => true,
             CommandResult::None => false,
         }
@@ -997,16 +1613,27 @@ impl<'a, 'm> MissionState<'a, 'm> {
         }
     }
     fn lines_to_draw(&self) -> impl Iterator<Item = &Line> {
-        self.lines_to_draw_unfiltered().iter().filter(|line| {
+        let run_diff = self
+            .run_diff
+            .as_ref()
+            .filter(|_| self.new_only && self.report_to_draw().is_some());
+        let matching_items = self
+            .matching_items
+            .as_ref()
+            .filter(|_| self.report_to_draw().is_some());
+        self.lines_to_draw_unfiltered().iter().filter(move |line| {
             // if this command failed, always show the output
-            matches!(self.cmd_result, CommandResult::Failure(..)) || line.matches(self.summary)
+            matches!(self.cmd_result, CommandResult::Failure(..))
+                || (line.matches(self.summary)
+                    && run_diff.is_none_or(|diff| diff.is_new(line.item_idx))
+                    && matching_items.is_none_or(|items| items.contains(&line.item_idx)))
         })
     }
     fn report_to_draw(&self) -> Option<&Report> {
         self.cmd_result
             .report()
             .filter(|_| !self.raw_output)
-            .filter(|report| !self.mission.is_success(report))
+            .filter(|report| !self.stage_mission().is_success(report))
     }
     fn update_wrap(&mut self) {
         let width = self.width.saturating_sub(1);
@@ -1063,6 +1690,7 @@ impl<'a, 'm> MissionState<'a, 'm> {
             area.scrollbar(self.scroll, content_height)
         };
         let mut top_item_idx = None;
+        let selected_item_idx = self.displayed_selected_item_idx();
         let top = if self.reverse && self.page_height() > content_height {
             self.page_height() - content_height
         } else {
@@ -1070,6 +1698,7 @@ impl<'a, 'm> MissionState<'a, 'm> {
         };
         #[allow(clippy::cast_possible_truncation)]
         let top = area.top + top as u16;
+        self.content_top = Some(top);
         for y in area.top..top {
             goto_line(w, y)?;
             clear_line(w)?;
@@ -1090,7 +1719,18 @@ impl<'a, 'm> MissionState<'a, 'm> {
             if let Some((line_idx, line)) = lines.next() {
                 top_item_idx.get_or_insert(line.item_idx);
                 line.line_type.draw(w, line.item_idx)?;
-                write!(w, " ")?;
+                let is_new = matches!(line.line_type, LineType::Title(_))
+                    && self
+                        .run_diff
+                        .as_ref()
+                        .is_some_and(|diff| diff.is_new(line.item_idx));
+                if selected_item_idx == Some(line.item_idx) {
+                    write!(w, "\u{1b}[38;5;{}m▌\u{1b}[0m", skin.selected_item_fg())?;
+                } else if is_new {
+                    write!(w, "\u{1b}[1m\u{1b}[38;5;{}m+\u{1b}[0m", skin.new_item_fg())?;
+                } else {
+                    write!(w, " ")?;
+                }
                 if width > line.line_type.cols() + 1 {
                     let mut tline = &line.content;
 
@@ -1167,20 +1807,29 @@ impl<'a, 'm> MissionState<'a, 'm> {
         // then the rest (content). The help page, when open, replaces the
         // content and keeps the status line.
         let h = self.height;
-        let has_help = self.help_page.is_some();
+        let has_help = self.is_help();
         // status line shows whenever the help page is up (h >= 1), otherwise
         // only once the badges and "computing" line are already in.
         let status_min_height = if has_help { 1 } else { 3 };
+        // the dashboard, when there's one, comes just before the content
+        let dh = self.dashboard_height();
+        self.content_top = None;
+        self.job_badges_zone = None;
         if self.reverse {
             if h >= status_min_height {
                 self.draw_status_line(w, 0)?;
             }
             if let Some(help_page) = self.help_page.as_mut() {
                 help_page.draw(w, Area::new(0, 1, self.width, h.saturating_sub(1)))?;
+            } else if let Some(explain_page) = self.explain_page.as_mut() {
+                explain_page.draw(w, Area::new(0, 1, self.width, h.saturating_sub(1)))?;
             } else {
-                if h >= 4 {
+                if h >= 4 + dh {
                     self.draw_content(w, 1)?;
                 }
+                if dh > 0 && h >= 4 {
+                    self.draw_dashboard(w, h - 3)?;
+                }
                 if h >= 2 {
                     self.draw_computing(w, h - 2)?;
                     self.draw_message(w, h - 2)?;
@@ -1192,6 +1841,8 @@ impl<'a, 'm> MissionState<'a, 'm> {
         } else {
             if let Some(help_page) = self.help_page.as_mut() {
                 help_page.draw(w, Area::new(0, 0, self.width, h.saturating_sub(1)))?;
+            } else if let Some(explain_page) = self.explain_page.as_mut() {
+                explain_page.draw(w, Area::new(0, 0, self.width, h.saturating_sub(1)))?;
             } else {
                 if h >= 1 {
                     self.draw_badges(w, 0)?;
@@ -1200,8 +1851,11 @@ impl<'a, 'm> MissionState<'a, 'm> {
                     self.draw_computing(w, 1)?;
                     self.draw_message(w, 1)?; // drawn over the "computing..." line
                 }
-                if h >= 4 {
-                    self.draw_content(w, 2)?;
+                if dh > 0 && h >= 4 {
+                    self.draw_dashboard(w, 2)?;
+                }
+                if h >= 4 + dh {
+                    self.draw_content(w, 2 + dh)?;
                 }
             }
             if h >= status_min_height {
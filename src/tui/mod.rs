@@ -1,5 +1,6 @@
 pub mod app;
 mod app_state;
+mod dashboard;
 mod dialog;
 mod drawing;
 mod focus_file;
@@ -14,6 +15,7 @@ mod wrap;
 
 pub use {
     app_state::*,
+    dashboard::*,
     dialog::*,
     drawing::*,
     focus_file::*,
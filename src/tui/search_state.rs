@@ -2,14 +2,20 @@ use {
     crate::*,
     anyhow::Result,
     crokey::KeyCombination,
+    lazy_regex::regex,
     termimad::InputField,
 };
 
 /// Search related state, part of the app state
 pub struct SearchState {
     mode: SearchMode,
+    /// how patterns are matched (regex, case insensitive, whole word)
+    pattern_options: PatternOptions,
     /// the search input field
     input: InputField,
+    /// the pattern compiled from the input and options, kept so that
+    /// it's not compiled again on every search or draw
+    pattern: Result<Pattern, regex::Error>,
     /// whether the app state is up to date with the search
     up_to_date: bool,
     /// Locations matching the `search_input` content
@@ -25,7 +31,9 @@ impl Default for SearchState {
         let founds = Default::default();
         Self {
             mode: SearchMode::Pattern,
+            pattern_options: PatternOptions::default(),
             input: search_input,
+            pattern: Ok(Pattern::literal("")),
             up_to_date: true,
             founds,
             selected_found: 0,
@@ -56,6 +64,7 @@ impl SearchState {
     ) {
         if mode != self.mode {
             self.input.clear();
+            self.compile_pattern();
         }
         self.mode = mode;
         self.input.set_focus(true);
@@ -71,11 +80,13 @@ impl SearchState {
     }
     pub fn unfocus_and_clear(&mut self) {
         self.input.clear();
+        self.compile_pattern();
         self.up_to_date = false;
         self.input.set_focus(false);
     }
     pub fn clear(&mut self) {
         self.input.clear();
+        self.compile_pattern();
         self.up_to_date = false;
     }
     pub fn next_match(&mut self) {
@@ -97,25 +108,44 @@ impl SearchState {
         &mut self,
         key: KeyCombination,
     ) -> bool {
-        if self.input.focused() {
-            if self.input.apply_key_combination(key) {
-                self.up_to_date = false;
-                return true;
-            }
+        if self.input.focused() && self.input.apply_key_combination(key) {
+            self.compile_pattern();
+            self.up_to_date = false;
+            return true;
         }
         false
     }
-    pub fn search(&self) -> Search {
+    pub fn toggle_regex(&mut self) {
+        self.pattern_options.regex ^= true;
+        self.compile_pattern();
+        self.up_to_date = false;
+    }
+    pub fn toggle_case_insensitive(&mut self) {
+        self.pattern_options.case_insensitive ^= true;
+        self.compile_pattern();
+        self.up_to_date = false;
+    }
+    pub fn toggle_whole_word(&mut self) {
+        self.pattern_options.whole_word ^= true;
+        self.compile_pattern();
+        self.up_to_date = false;
+    }
+    fn compile_pattern(&mut self) {
+        self.pattern = Pattern::new(self.input.get_content(), self.pattern_options);
+    }
+    /// Return the search, or None if the input isn't a valid regular
+    /// expression while in regex mode
+    pub fn search(&self) -> Option<Search> {
         match self.mode {
-            SearchMode::Pattern => Search::Pattern(Pattern {
-                pattern: self.input.get_content(),
-            }),
-            SearchMode::ItemIdx => Search::ItemIdx(self.input.get_content().parse().unwrap_or(0)),
+            SearchMode::Pattern => self.pattern.as_ref().ok().cloned().map(Search::Pattern),
+            SearchMode::ItemIdx => Some(Search::ItemIdx(
+                self.input.get_content().parse().unwrap_or(0),
+            )),
         }
     }
     pub fn is_invalid(&self) -> bool {
         match self.mode {
-            SearchMode::Pattern => false,
+            SearchMode::Pattern => self.pattern.is_err(),
             SearchMode::ItemIdx => {
                 if self.input.is_empty() {
                     false
@@ -153,26 +183,30 @@ impl SearchState {
     pub fn founds(&self) -> &[Found] {
         &self.founds
     }
-    /// Draw the input with its '/', at the given position, with the specified width
+    /// The text drawn before the input: ':' when going to an item, '/'
+    /// preceded by the pattern options flags (eg "ri/") otherwise
+    fn prefix(&self) -> String {
+        match self.mode {
+            SearchMode::ItemIdx => ":".to_string(),
+            SearchMode::Pattern => format!("{}/", self.pattern_options.flags()),
+        }
+    }
+    /// Draw the input with its prefix, at the given position, with the specified width
     pub fn draw_prefixed_input(
         &mut self,
         w: &mut W,
         x: u16,
         y: u16,
         prefix_style: &str,
-        width: u16, // must be > 1
+        width: u16, // must be > the prefix width
     ) -> Result<()> {
         goto_line(w, y)?;
-        draw(
-            w,
-            prefix_style,
-            if self.mode == SearchMode::ItemIdx {
-                ":"
-            } else {
-                "/"
-            },
-        )?;
-        self.input.change_area(x + 1, y, width - 1);
+        let prefix = self.prefix();
+        draw(w, prefix_style, &prefix)?;
+        #[allow(clippy::cast_possible_truncation)]
+        let prefix_width = prefix.len() as u16;
+        self.input
+            .change_area(x + prefix_width, y, width - prefix_width);
         self.input.display_on(w)?;
         Ok(())
     }
@@ -184,6 +218,8 @@ impl SearchState {
         if self.founds.is_empty() {
             if self.mode == SearchMode::ItemIdx && self.is_invalid() {
                 t_line.add_tstring(style, "integer expected");
+            } else if self.is_invalid() {
+                t_line.add_tstring(style, "invalid regex");
             } else {
                 t_line.add_tstring(style, "no match");
             }